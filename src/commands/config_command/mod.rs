@@ -0,0 +1,115 @@
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+use crate::{
+    helpers::project_helper,
+    user_config::{
+        config_layers::{get_config_layers, merge_config_layers, ConfigLayerKind},
+        UserConfig,
+    },
+};
+
+/// Sets the subcommand and the corresponding arguments
+pub fn set_subcommand(app: Command) -> Command {
+    let config_subcommand = Command::new("config")
+        .about("Inspect the tool's configuration.")
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("show")
+                .about("Print the project's configuration")
+                .after_help(
+                    "The final configuration merges, from the lowest to the \
+                    highest precedence, the user-level configuration, the \
+                    workspace's one, the project's one and the preset chosen \
+                    with --preset (or the default one). Each of them comes \
+                    after the configurations it extends. With --dir, the \
+                    .nextbutler.json files of the folders holding it \
+                    override them.",
+                )
+                .arg(
+                    Arg::new("resolved")
+                        .help("Print the final configuration, merging every layer")
+                        .long("resolved")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("origin")
+                        .help("Print every value next to the layer it came from")
+                        .long("origin")
+                        .requires("resolved")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("dir")
+                        .help("Print the configuration of the files created inside the given folder")
+                        .long("dir")
+                        .value_name("DIR"),
+                ),
+        )
+        .subcommand(
+            Command::new("schema")
+                .about("Print the JSON Schema of the configuration files")
+                .after_help(
+                    "Refer to it from the configuration with \"$schema\", so \
+                    editors can autocomplete and validate it. `nb init` \
+                    creates it as nextbutler/nextbutler.schema.json.",
+                ),
+        );
+
+    app.subcommand(config_subcommand)
+}
+
+/// Executes the command
+pub fn exec_command(cmd_args: &ArgMatches) -> Result<(), String> {
+    match cmd_args.subcommand() {
+        Some(("show", show_args)) => show(show_args),
+        Some(("schema", _)) => print_schema(),
+        _ => Err(String::from("Unknown command")),
+    }
+}
+
+fn show(args: &ArgMatches) -> Result<(), String> {
+    let target_dir = args
+        .get_one::<String>("dir")
+        .map(project_helper::resolve_arg_path);
+    let mut layers = get_config_layers(target_dir.as_deref())?;
+    if !args.get_flag("resolved") {
+        layers.retain(|layer| {
+            matches!(
+                layer.kind,
+                ConfigLayerKind::Project | ConfigLayerKind::Preset | ConfigLayerKind::Directory
+            )
+        });
+        if layers.is_empty() {
+            println!("The project has no configuration, so the defaults are used:");
+            return print_json(&UserConfig::get_default());
+        }
+    }
+
+    let (config, origins) = merge_config_layers(&layers);
+    if !args.get_flag("origin") {
+        return print_json(&config);
+    }
+
+    for (value_path, origin) in origins {
+        let value = value_path
+            .split('.')
+            .try_fold(&config, |value, key| value.get(key))
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        println!("{} = {}  # {}", value_path, value, origin);
+    }
+
+    Ok(())
+}
+
+fn print_schema() -> Result<(), String> {
+    let schema = UserConfig::get_json_schema()?;
+    println!("{}", String::from_utf8_lossy(&schema));
+    Ok(())
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
@@ -2,7 +2,10 @@ use std::{fs, path::PathBuf};
 
 use clap::Command;
 
-use crate::{helpers::file_helper, template::{create_pages_templates, create_components_templates, create_stylesheets_templates}, user_config::UserConfig};
+use crate::{
+    constants::CONFIG_SCHEMA_FILE_NAME, helpers::file_helper, template::create_default_templates,
+    user_config::UserConfig,
+};
 use colored::Colorize;
 
 /// Sets the subcommand and the corresponding arguments
@@ -14,6 +17,8 @@ pub fn set_subcommand(app: Command) -> Command {
             "This command does the following: \n\
                     - Creates the tool's directory (nextbutler) inside the root dir \n\
                     - Creates the configuration file (nextbutler/nextbutler.json) \n\
+                    - Creates its JSON Schema (nextbutler/nextbutler.schema.json), \
+                    so editors can autocomplete and validate it \n\
                     - Creates the default templates as custom ones, as examples",
         );
 
@@ -28,16 +33,17 @@ pub fn exec_command() -> Result<(), String> {
     fs::create_dir_all(nextbutler_path.clone())
         .map_err(|err| format!("Error creating nextbutler directory: {}", err))?;
 
-    // Create configuration file
+    // Create configuration file, referring to its schema
     let user_config_path = nextbutler_path.join("nextbutler.json");
     file_helper::create(&user_config_path, UserConfig::get_default_as_vec()?)
         .map_err(|err| format!("Error creating configuration file: {}", err))?;
+    let schema_path = nextbutler_path.join(CONFIG_SCHEMA_FILE_NAME);
+    file_helper::create(&schema_path, UserConfig::get_json_schema()?)
+        .map_err(|err| format!("Error creating the configuration's schema: {}", err))?;
 
-    // Create page templates folder
+    // Create templates folder
     println!("Creating templates...");
-    create_pages_templates(nextbutler_path.join("templates/pages/"))?;
-    create_components_templates(nextbutler_path.join("templates/components/"))?;
-    create_stylesheets_templates(nextbutler_path.join("templates/styles/"))?;
+    create_default_templates(nextbutler_path.join("templates/"))?;
 
     println!("{}", "Configuration structure created successfuly".green());
 
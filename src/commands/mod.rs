@@ -6,3 +6,9 @@ pub mod init_command;
 
 /// Module that represents the 'rm' command
 pub mod rm_command;
+
+/// Module that represents the 'template' command
+pub mod template_command;
+
+/// Module that represents the 'config' command
+pub mod config_command;
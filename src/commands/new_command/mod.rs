@@ -2,7 +2,22 @@ pub mod new_comp;
 pub mod new_page;
 pub mod new_style;
 
-use clap::{ArgMatches, Command};
+use std::path::PathBuf;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use path_clean::PathClean;
+
+use crate::{
+    helpers::{file_helper, project_helper},
+    react_extension::ReactExtension,
+    template::{
+        get_custom_template,
+        template_variables::{merge_template_vars, TemplateVars},
+        Template,
+    },
+    user_config::UserConfig,
+    CreateableFileType,
+};
 
 /// Sets the subcommand and the corresponding arguments
 pub fn set_subcommand(app: Command) -> Command {
@@ -30,3 +45,134 @@ pub fn exec_command(cmd_args: &ArgMatches) -> Result<(), String> {
         _ => Err(String::from("Unknown command")),
     }
 }
+
+/// Adds the arguments shared by every subcommand that renders a template
+pub fn set_template_args(cmd: Command) -> Command {
+    cmd.arg(
+        Arg::new("var")
+            .help("Define a template variable. Can be used multiple times")
+            .long("var")
+            .value_name("KEY=VALUE")
+            .action(ArgAction::Append),
+    )
+    .arg(
+        Arg::new("vars")
+            .help("JSON file containing an object with template variables")
+            .long("vars")
+            .value_name("FILE"),
+    )
+    .arg(
+        Arg::new("strict")
+            .help("Fail if the template uses an undefined variable")
+            .long("strict")
+            .action(ArgAction::SetTrue),
+    )
+    .arg(
+        Arg::new("explain")
+            .help("Print which template is used and where it was found")
+            .long("explain")
+            .action(ArgAction::SetTrue),
+    )
+}
+
+/// Prints the template that will be used and the layer it was resolved from,
+/// if the --explain flag is present
+pub fn explain_template(cmd_args: &ArgMatches, template: &Template) {
+    if cmd_args.get_flag("explain") {
+        println!(
+            "Using {} (resolved from the {} templates)",
+            template.get_display_name(),
+            template.get_layer()
+        );
+    }
+}
+
+/// Templates are rendered in strict mode if the --strict flag is present or
+/// if it's enabled in the configuration file
+pub fn use_strict_templates(cmd_args: &ArgMatches, user_config: &UserConfig) -> bool {
+    cmd_args.get_flag("strict") || user_config.strict_templates.unwrap_or(false)
+}
+
+/// Merges the built-in template variables with the ones defined in the
+/// configuration file and the ones given as arguments
+pub fn get_template_vars(
+    cmd_args: &ArgMatches,
+    builtin_vars: TemplateVars,
+    config_vars: Option<&TemplateVars>,
+) -> Result<TemplateVars, String> {
+    let var_args: Vec<String> = cmd_args
+        .get_many::<String>("var")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+
+    let vars_file = cmd_args
+        .get_one::<String>("vars")
+        .map(|vars_file| project_helper::resolve_arg_path(vars_file).to_string_lossy().to_string());
+
+    merge_template_vars(builtin_vars, config_vars, vars_file.as_ref(), &var_args)
+}
+
+/// Gets the configuration to create a file with. The .nextbutler.json of the
+/// destination's folders override the rest of the configuration, so the
+/// folder is worked out first with `get_destination_dir`, which mustn't load
+/// any template nor ask anything
+pub fn get_destination_config(
+    get_destination_dir: impl FnOnce(&UserConfig) -> Result<PathBuf, String>,
+) -> Result<UserConfig, String> {
+    let usr_cfg = UserConfig::get()?;
+    let destination_dir = get_destination_dir(&usr_cfg)?;
+    Ok(UserConfig::get_for_dir(&destination_dir)?.unwrap_or(usr_cfg))
+}
+
+/// Path of the new file given as argument, without ".." segments
+pub fn get_path_arg(cmd_args: &ArgMatches, arg_name: &str) -> PathBuf {
+    let mut path_arg = PathBuf::from(cmd_args.get_one::<String>(arg_name).unwrap());
+    file_helper::rm_double_dots_from_path_buf(&mut path_arg);
+    path_arg.clean()
+}
+
+/// Gets the custom template given as argument or set in the configuration,
+/// if any. `None` means that the built-in template matching the file's
+/// extension has to be used
+pub fn get_template<'a>(
+    cmd_args: &ArgMatches,
+    config_template: Option<&String>,
+    file_type: &CreateableFileType,
+    extension: Option<&str>,
+    usr_cfg: &UserConfig,
+) -> Result<Option<Template<'a>>, String> {
+    match cmd_args.get_one::<String>("template").or(config_template) {
+        Some(template_name) => get_custom_template(
+            template_name,
+            file_type,
+            extension,
+            usr_cfg.template_extension_order.as_deref().unwrap_or_default(),
+        ),
+        None => Ok(None),
+    }
+}
+
+/// Extension defined by the configuration file, if it sets `typescript` or
+/// `jsx`. API pages are never .jsx nor .tsx
+pub fn get_config_extension(
+    typescript: Option<bool>,
+    jsx: Option<bool>,
+    is_api: bool,
+) -> Option<ReactExtension> {
+    if typescript.is_none() && jsx.is_none() {
+        return None;
+    }
+
+    let usr_cfg_ts = typescript.unwrap_or(false);
+    let usr_cfg_jsx = jsx.unwrap_or(false);
+
+    if usr_cfg_ts && usr_cfg_jsx && !is_api {
+        Some("tsx".into())
+    } else if usr_cfg_ts && (!usr_cfg_jsx || is_api) {
+        Some("ts".into())
+    } else if !usr_cfg_ts && usr_cfg_jsx && !is_api {
+        Some("jsx".into())
+    } else {
+        Some("js".into())
+    }
+}
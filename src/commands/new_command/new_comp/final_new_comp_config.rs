@@ -2,152 +2,185 @@ use std::{path::{Path, PathBuf}, collections::BTreeMap};
 
 use clap::ArgMatches;
 use convert_case::{Case, Casing};
-use path_clean::PathClean;
 
 use crate::{
-    helpers::file_helper,
+    commands::new_command::{
+        get_config_extension, get_destination_config, get_path_arg, get_template,
+        get_template_vars, use_strict_templates,
+    },
+    helpers::project_helper::ProjectDirs,
     react_extension::ReactExtension,
     template::{
-        get_custom_template, get_default_template, Template,
+        front_matter::FrontMatter, get_default_template, render_path_pattern,
+        render_path_pattern_dir, Template,
     },
     user_config::{UserConfig, UserNewComponentConfig},
     CreateableFileType,
 };
 
+/// Default path of the new components
+const COMP_PATH_PATTERN: &str = "{{folder}}/{{path}}.{{ext}}";
+
 pub struct FinalNewCompConfig<'a> {
     /// Where the new component will be located
     pub comp_final_path: PathBuf,
     /// Template to be used
     pub template: Template<'a>,
-    pub template_vars: BTreeMap<String, String>
+    pub template_vars: BTreeMap<String, String>,
+    /// Fail if the template uses an undefined variable
+    pub strict_templates: bool,
 }
 
 impl<'a> FinalNewCompConfig<'a> {
     pub fn new(comp_args: &ArgMatches) -> Result<Self, String> {
-        let usr_comp_cfg = UserConfig::get()?.get_component_config();
+        let usr_cfg =
+            get_destination_config(|usr_cfg| Self::get_destination_dir(comp_args, usr_cfg))?;
+        Self::build(comp_args, usr_cfg)
+    }
 
-        let mut path_arg = PathBuf::from(comp_args.get_one::<String>("component_path").unwrap());
-        file_helper::rm_double_dots_from_path_buf(&mut path_arg);
-        path_arg = path_arg.clean();
+    fn build(comp_args: &ArgMatches, usr_cfg: UserConfig) -> Result<Self, String> {
+        let strict_templates = use_strict_templates(comp_args, &usr_cfg);
+        let project_dirs = ProjectDirs::get(&usr_cfg)?;
+        let path_arg = get_path_arg(comp_args, "component_path");
 
         let file_type = CreateableFileType::Component;
-        let comp_extension =
-            Self::get_extension_to_use(comp_args, &usr_comp_cfg, &file_type, &path_arg);
-        let destination_folder = match comp_args.get_one::<String>("folder") {
-            Some(destination_folder) => destination_folder.to_owned(),
-            None => usr_comp_cfg
-                .folder
-                .clone()
-                .unwrap_or(String::from("components")),
-        };
-        let comp_final_path =
-            Self::get_comp_final_path(path_arg.to_owned(), &comp_extension, &destination_folder)?;
-
-        let template = Self::get_template(
-            comp_args.get_one::<String>("template"),
-            &usr_comp_cfg,
+        let usr_comp_cfg = usr_cfg.to_owned().get_component_config();
+        let requested_extension = Self::get_requested_extension(comp_args, &path_arg);
+        let config_extension =
+            get_config_extension(usr_comp_cfg.typescript, usr_comp_cfg.jsx, false);
+        let custom_template = get_template(
+            comp_args,
+            usr_comp_cfg.template.as_ref(),
             &file_type,
+            requested_extension.or(config_extension).map(<&str>::from),
+            &usr_cfg,
         )?;
+        let front_matter = match &custom_template {
+            Some(template) => template.load()?.front_matter,
+            None => FrontMatter::default(),
+        };
 
-        let new_page_name = path_arg
-            .file_stem()
-            .ok_or("Must specify the page's name")?
-            .to_string_lossy()
-            .to_case(Case::Pascal);
-        let template_vars = BTreeMap::from([
-            ("name".to_owned(), new_page_name)
-        ]);
+        let mut template_vars = Self::get_vars(comp_args, &path_arg, &usr_comp_cfg)?;
+        front_matter.resolve_variables(&mut template_vars)?;
+
+        let comp_extension = requested_extension
+            .or_else(|| front_matter.extension.as_deref().map(ReactExtension::from))
+            .or_else(|| {
+                custom_template
+                    .as_ref()
+                    .and_then(Template::get_target_extension)
+                    .map(|extension| extension.as_str().into())
+            })
+            .or(config_extension)
+            .unwrap_or(ReactExtension::Js);
+        let template = custom_template
+            .unwrap_or_else(|| get_default_template(&file_type, (&comp_extension).into()));
+        if path_arg.ends_with("/") {
+            return Err(String::from("Must specify the component's name"));
+        }
+        let comp_final_path = render_path_pattern(
+            front_matter
+                .path
+                .as_deref()
+                .or(usr_comp_cfg.path.as_deref())
+                .unwrap_or(COMP_PATH_PATTERN),
+            &template_vars,
+            &project_dirs,
+            &Self::check_destination_dir(Self::get_folder(comp_args, &project_dirs))?,
+            &path_arg,
+            (&comp_extension).into(),
+            strict_templates,
+        )?;
 
         Ok(Self {
             comp_final_path,
             template,
-            template_vars
+            template_vars,
+            strict_templates,
         })
     }
 
-    fn get_comp_final_path(
-        path_arg: PathBuf,
-        extension: &ReactExtension,
-        destination_folder: &String,
+    /// Folder where the configuration creates the component, before knowing
+    /// its template
+    fn get_destination_dir(
+        comp_args: &ArgMatches,
+        usr_cfg: &UserConfig,
     ) -> Result<PathBuf, String> {
-        let path_arg = path_arg
-            .strip_prefix("/")
-            .unwrap_or(path_arg.as_path())
-            .to_path_buf();
-
-        if path_arg.ends_with("/") {
-            return Err(String::from("Must specify the component's name"));
-        }
+        let project_dirs = ProjectDirs::get(usr_cfg)?;
+        let path_arg = get_path_arg(comp_args, "component_path");
+        let usr_comp_cfg = usr_cfg.to_owned().get_component_config();
+        let comp_extension = Self::get_requested_extension(comp_args, &path_arg)
+            .or(get_config_extension(usr_comp_cfg.typescript, usr_comp_cfg.jsx, false))
+            .unwrap_or(ReactExtension::Js);
+
+        render_path_pattern_dir(
+            usr_comp_cfg.path.as_deref().unwrap_or(COMP_PATH_PATTERN),
+            &Self::get_vars(comp_args, &path_arg, &usr_comp_cfg)?,
+            &project_dirs,
+            &Self::get_folder(comp_args, &project_dirs),
+            &path_arg,
+            (&comp_extension).into(),
+        )
+    }
 
-        // Base path of the new component
-        let mut final_path = PathBuf::new();
+    /// Variables of the component's template, besides the ones defined by
+    /// the template itself
+    fn get_vars(
+        comp_args: &ArgMatches,
+        path_arg: &Path,
+        usr_comp_cfg: &UserNewComponentConfig,
+    ) -> Result<BTreeMap<String, String>, String> {
+        let new_comp_name = path_arg
+            .file_stem()
+            .ok_or("Must specify the component's name")?
+            .to_string_lossy()
+            .to_case(Case::Pascal);
+        get_template_vars(
+            comp_args,
+            BTreeMap::from([("name".to_owned(), new_comp_name)]),
+            usr_comp_cfg.variables.as_ref(),
+        )
+    }
 
-        if file_helper::is_src_present()? {
-            final_path.push("src/");
+    /// Folder given as argument, inside the source folder, or the components
+    /// folder
+    fn get_folder(comp_args: &ArgMatches, project_dirs: &ProjectDirs) -> PathBuf {
+        match comp_args.get_one::<String>("folder") {
+            Some(destination_folder) => project_dirs.src_dir.join(destination_folder),
+            None => project_dirs.components_dir.to_owned(),
         }
+    }
 
-        final_path.push(destination_folder);
-        if !final_path.exists() {
+    /// Checks the folder where components are created exists
+    fn check_destination_dir(destination_dir: PathBuf) -> Result<PathBuf, String> {
+        if !destination_dir.exists() {
             return Err(String::from("Couldn't find destination folder"));
         }
 
-        final_path.push(path_arg);
-        final_path.set_extension(extension);
-
-        Ok(final_path)
+        Ok(destination_dir)
     }
 
-    fn get_template(
-        template_arg: Option<&String>,
-        user_new_comp_config: &UserNewComponentConfig,
-        file_type: &CreateableFileType,
-    ) -> Result<Template<'a>, String> {
-        if let Some(template_name) = template_arg {
-            get_custom_template(template_name, file_type)
-        } else if let Some(template_name) = &user_new_comp_config.template {
-            get_custom_template(template_name, file_type)
-        } else {
-            Ok(get_default_template(file_type))
-        }
-    }
-
-    fn get_extension_to_use(
-        page_args: &ArgMatches,
-        user_new_comp_config: &UserNewComponentConfig,
-        page_type: &CreateableFileType,
-        path_arg: &Path,
-    ) -> ReactExtension {
+    /// Extension requested with the component's path or the flags, if any
+    fn get_requested_extension(comp_args: &ArgMatches, path_arg: &Path) -> Option<ReactExtension> {
         if let Some(path_arg_extension) = path_arg.extension() {
-            return path_arg_extension.into();
+            return Some(path_arg_extension.into());
         }
 
-        let js_flag = page_args.get_flag("js");
-        let ts_flag = page_args.get_flag("ts");
-        let jsx_flag = page_args.get_flag("jsx");
-        let tsx_flag = page_args.get_flag("tsx");
+        let js_flag = comp_args.get_flag("js");
+        let ts_flag = comp_args.get_flag("ts");
+        let jsx_flag = comp_args.get_flag("jsx");
+        let tsx_flag = comp_args.get_flag("tsx");
 
         if !js_flag && !ts_flag && !jsx_flag && !tsx_flag {
-            let usr_cfg_ts = user_new_comp_config.typescript.unwrap_or(false);
-            let usr_cfg_jsx = user_new_comp_config.jsx.unwrap_or(false);
-            let is_api = matches!(page_type, CreateableFileType::ApiPage);
-
-            if usr_cfg_ts && usr_cfg_jsx && !is_api {
-                "tsx".into()
-            } else if usr_cfg_ts && (!usr_cfg_jsx || is_api) {
-                "ts".into()
-            } else if !usr_cfg_ts && usr_cfg_jsx && !is_api {
-                "jsx".into()
-            } else {
-                "js".into()
-            }
-        } else {
-            ReactExtension::guess(
-                js_flag,
-                ts_flag,
-                jsx_flag,
-                tsx_flag,
-                None::<UserNewComponentConfig>,
-            )
+            return None;
         }
+
+        Some(ReactExtension::guess(
+            js_flag,
+            ts_flag,
+            jsx_flag,
+            tsx_flag,
+            None::<UserNewComponentConfig>,
+        ))
     }
 }
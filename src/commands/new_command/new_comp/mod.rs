@@ -1,7 +1,9 @@
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use colored::Colorize;
 
-use crate::template::create_from_template;
+use crate::{template::create_from_template, CreateableFileType};
+
+use super::{explain_template, set_template_args};
 
 use self::final_new_comp_config::FinalNewCompConfig;
 
@@ -9,7 +11,7 @@ mod final_new_comp_config;
 
 /// Sets the new component subcommand
 pub fn set_subcommand(app: Command) -> Command {
-    app.subcommand(
+    app.subcommand(set_template_args(
         Command::new("component")
             .about("Create a new component file, inside /components/")
             .arg(Arg::new("component_path").required(true).help(
@@ -55,18 +57,32 @@ pub fn set_subcommand(app: Command) -> Command {
                     .help("Define the base folder of the component")
                     .long("folder"),
             )
+            .arg(
+                Arg::new("package")
+                    .help("Create the component in the given shared package of the workspace")
+                    .long("package")
+                    .value_name("NAME")
+                    .conflicts_with("app"),
+            )
             .arg(
                 Arg::new("template")
                     .help("The name of your custom template")
                     .long("template"),
             ),
-    )
+    ))
 }
 
 /// Creates a new component based on the given arguments and the configuration file
 pub fn exec_command(comp_args: &ArgMatches) -> Result<(), String> {
     let component_config = FinalNewCompConfig::new(comp_args)?;
-    create_from_template(&component_config.comp_final_path, component_config.template, &component_config.template_vars)?;
+    explain_template(comp_args, &component_config.template);
+    create_from_template(
+        &component_config.comp_final_path,
+        &CreateableFileType::Component,
+        component_config.template,
+        &component_config.template_vars,
+        component_config.strict_templates,
+    )?;
     println!(
         "Component successfuly created at {}",
         &component_config.comp_final_path.to_string_lossy().green()
@@ -5,107 +5,217 @@ use std::{
 
 use clap::ArgMatches;
 use convert_case::{Case, Casing};
-use path_clean::PathClean;
 
 use crate::{
-    helpers::file_helper,
+    commands::new_command::{
+        get_config_extension, get_destination_config, get_path_arg, get_template,
+        get_template_vars, use_strict_templates,
+    },
+    helpers::project_helper::ProjectDirs,
     react_extension::ReactExtension,
-    template::{get_custom_template, get_default_template, Template},
+    template::{
+        front_matter::FrontMatter, get_default_page_template, get_default_template,
+        render_path_pattern, render_path_pattern_dir, Template,
+    },
     user_config::{UserConfig, UserNewPageConfig},
-    CreateableFileType,
+    CreateableFileType, NextRouter,
 };
 
+/// Default path of the pages created with the page router
+const PAGE_ROUTER_PATH_PATTERN: &str = "{{folder}}/{{path}}.{{ext}}";
+/// Default path of the pages created with the app router
+const APP_ROUTER_PATH_PATTERN: &str = "{{folder}}/{{path}}/page.{{ext}}";
+
 pub struct FinalNewPageConfig<'a> {
     /// Where the new page will be located
     pub page_final_path: PathBuf,
+    /// Whether it's a page or an API page
+    pub page_type: CreateableFileType,
     pub template: Template<'a>,
     pub template_vars: BTreeMap<String, String>,
+    /// Fail if the template uses an undefined variable
+    pub strict_templates: bool,
 }
 
 impl<'a> FinalNewPageConfig<'a> {
     pub fn new(page_args: &ArgMatches) -> Result<Self, String> {
-        let usr_page_cfg = UserConfig::get()?.get_page_config();
-        let mut path_arg = PathBuf::from(page_args.get_one::<String>("page_path").unwrap());
-        file_helper::rm_double_dots_from_path_buf(&mut path_arg);
-        path_arg = path_arg.clean();
+        let usr_cfg =
+            get_destination_config(|usr_cfg| Self::get_destination_dir(page_args, usr_cfg))?;
+        Self::build(page_args, usr_cfg)
+    }
 
-        let page_type = if Self::is_api(&path_arg) {
-            CreateableFileType::ApiPage
-        } else {
-            CreateableFileType::Page
-        };
+    fn build(page_args: &ArgMatches, usr_cfg: UserConfig) -> Result<Self, String> {
+        let strict_templates = use_strict_templates(page_args, &usr_cfg);
+        let project_dirs = ProjectDirs::get(&usr_cfg)?;
+        let usr_page_cfg = usr_cfg.to_owned().get_page_config();
+        let path_arg = get_path_arg(page_args, "page_path");
+
+        let page_type = Self::get_page_type(&path_arg);
         let use_page_router = Self::use_page_router(
             page_args.get_flag("page-router"),
             page_args.get_flag("app-router"),
             &usr_page_cfg,
         );
+        let mut template_vars = Self::get_vars(page_args, &path_arg, &usr_page_cfg)?;
 
-        let new_page_name = path_arg
-            .file_stem()
-            .ok_or("Must specify the page's name")?
-            .to_string_lossy()
-            .to_case(Case::Pascal);
-        let template_vars = BTreeMap::from([("name".to_owned(), new_page_name)]);
-
-        let template = Self::get_template(
-            page_args.get_one::<String>("template"),
-            &usr_page_cfg,
+        let flags_extension = Self::get_flags_extension(page_args);
+        let config_extension = get_config_extension(
+            usr_page_cfg.typescript,
+            usr_page_cfg.jsx,
+            page_type == CreateableFileType::ApiPage,
+        );
+        let custom_template = get_template(
+            page_args,
+            usr_page_cfg.template.as_ref(),
             &page_type,
+            flags_extension.or(config_extension).map(<&str>::from),
+            &usr_cfg,
         )?;
+        let front_matter = match &custom_template {
+            Some(template) => template.load()?.front_matter,
+            None => FrontMatter::default(),
+        };
+        front_matter.resolve_variables(&mut template_vars)?;
 
-        let page_final_extension =
-            Self::get_extension_to_use(page_args, &usr_page_cfg, &page_type, &template)?;
-        let page_final_path =
-            Self::setup_page_path(path_arg, use_page_router, &page_final_extension)?;
+        let page_final_extension = flags_extension
+            .or_else(|| front_matter.extension.as_deref().map(ReactExtension::from))
+            .or_else(|| {
+                custom_template
+                    .as_ref()
+                    .and_then(Template::get_target_extension)
+                    .map(|extension| extension.as_str().into())
+            })
+            .or(config_extension)
+            .unwrap_or(ReactExtension::Js);
+        let template = custom_template.unwrap_or_else(|| match page_type {
+            CreateableFileType::Page if use_page_router => get_default_page_template(
+                (&page_final_extension).into(),
+                &NextRouter::PageRouter,
+            ),
+            _ => get_default_template(&page_type, (&page_final_extension).into()),
+        });
+        if path_arg.ends_with("/") {
+            return Err(String::from("Must specify the page's name"));
+        }
+        let page_final_path = render_path_pattern(
+            front_matter
+                .path
+                .as_deref()
+                .unwrap_or(Self::get_path_pattern(&usr_page_cfg, &page_type, use_page_router)),
+            &template_vars,
+            &project_dirs,
+            &Self::get_router_dir(&project_dirs, use_page_router)?,
+            &path_arg,
+            (&page_final_extension).into(),
+            strict_templates,
+        )?;
 
         Ok(Self {
             page_final_path,
+            page_type,
             template,
             template_vars,
+            strict_templates,
         })
     }
 
-    /// Set the parents to page_path, based on the correct router (app or page router)
-    fn setup_page_path(
-        path_arg: PathBuf,
-        use_page_router: bool,
-        extension: &ReactExtension
+    /// Folder where the configuration creates the page, before knowing its
+    /// template
+    fn get_destination_dir(
+        page_args: &ArgMatches,
+        usr_cfg: &UserConfig,
     ) -> Result<PathBuf, String> {
-        let path_arg = path_arg
-            .strip_prefix("/")
-            .unwrap_or(path_arg.as_path())
-            .to_path_buf();
+        let project_dirs = ProjectDirs::get(usr_cfg)?;
+        let usr_page_cfg = usr_cfg.to_owned().get_page_config();
+        let path_arg = get_path_arg(page_args, "page_path");
 
-        if path_arg.ends_with("/") {
-            return Err(String::from("Must specify the page's name"));
-        }
+        let page_type = Self::get_page_type(&path_arg);
+        let use_page_router = Self::use_page_router(
+            page_args.get_flag("page-router"),
+            page_args.get_flag("app-router"),
+            &usr_page_cfg,
+        );
+        let page_extension = Self::get_flags_extension(page_args)
+            .or(get_config_extension(
+                usr_page_cfg.typescript,
+                usr_page_cfg.jsx,
+                page_type == CreateableFileType::ApiPage,
+            ))
+            .unwrap_or(ReactExtension::Js);
+        let router_dir = if use_page_router {
+            &project_dirs.pages_dir
+        } else {
+            &project_dirs.app_dir
+        };
 
-        // Base path of the new page
-        let mut final_path = PathBuf::new();
+        render_path_pattern_dir(
+            Self::get_path_pattern(&usr_page_cfg, &page_type, use_page_router),
+            &Self::get_vars(page_args, &path_arg, &usr_page_cfg)?,
+            &project_dirs,
+            router_dir,
+            &path_arg,
+            (&page_extension).into(),
+        )
+    }
 
-        if file_helper::is_src_present()? {
-            final_path.push("src/");
-        }
+    /// Variables of the page's template, besides the ones defined by the
+    /// template itself
+    fn get_vars(
+        page_args: &ArgMatches,
+        path_arg: &Path,
+        usr_page_cfg: &UserNewPageConfig,
+    ) -> Result<BTreeMap<String, String>, String> {
+        let new_page_name = path_arg
+            .file_stem()
+            .ok_or("Must specify the page's name")?
+            .to_string_lossy()
+            .to_case(Case::Pascal);
+        get_template_vars(
+            page_args,
+            BTreeMap::from([("name".to_owned(), new_page_name)]),
+            usr_page_cfg.variables.as_ref(),
+        )
+    }
 
-        if use_page_router {
-            final_path.push("pages/");
-            if !final_path.exists() {
-                return Err(String::from("Couldn't find destination folder"));
-            } else {
-                final_path.push(path_arg);
-            }
+    /// Pattern of the page's path set by the configuration, or the default
+    /// one of its router
+    fn get_path_pattern<'b>(
+        usr_page_cfg: &'b UserNewPageConfig,
+        page_type: &CreateableFileType,
+        use_page_router: bool,
+    ) -> &'b str {
+        match page_type {
+            CreateableFileType::ApiPage => usr_page_cfg.api_path.as_deref(),
+            _ => usr_page_cfg.path.as_deref(),
+        }
+        .unwrap_or(if use_page_router {
+            PAGE_ROUTER_PATH_PATTERN
         } else {
-            final_path.push("app/");
-            if !final_path.exists() {
-                return Err(String::from("Couldn't find destination folder"));
-            }
+            APP_ROUTER_PATH_PATTERN
+        })
+    }
 
-            final_path.push(format!("{}/page", path_arg.to_string_lossy()));
+    fn get_page_type(path_arg: &Path) -> CreateableFileType {
+        if Self::is_api(path_arg) {
+            CreateableFileType::ApiPage
+        } else {
+            CreateableFileType::Page
         }
+    }
+
+    /// Path of the router's folder (app or page router)
+    fn get_router_dir(project_dirs: &ProjectDirs, use_page_router: bool) -> Result<PathBuf, String> {
+        let router_dir = if use_page_router {
+            project_dirs.pages_dir.to_owned()
+        } else {
+            project_dirs.app_dir.to_owned()
+        };
 
-        final_path.set_extension(extension);
+        if !router_dir.exists() {
+            return Err(String::from("Couldn't find destination folder"));
+        }
 
-        Ok(final_path)
+        Ok(router_dir)
     }
 
     /// Returns true if the name starts with
@@ -117,51 +227,24 @@ impl<'a> FinalNewPageConfig<'a> {
         }
     }
 
-    fn get_extension_to_use(
-        page_args: &ArgMatches,
-        user_new_page_config: &UserNewPageConfig,
-        page_type: &CreateableFileType,
-        template: &Template
-    ) -> Result<ReactExtension, String> {
+    /// Extension requested with the flags, if any
+    fn get_flags_extension(page_args: &ArgMatches) -> Option<ReactExtension> {
         let js_flag = page_args.get_flag("js");
         let ts_flag = page_args.get_flag("ts");
         let jsx_flag = page_args.get_flag("jsx");
         let tsx_flag = page_args.get_flag("tsx");
 
         if !js_flag && !ts_flag && !jsx_flag && !tsx_flag {
-            // Get extension from template
-            if let Template::Path(tmpl_path) = template {
-                let tmpl_stem = tmpl_path.file_stem();
-                if let Some(tmpl_stem) = tmpl_stem {
-                    if let Some(tmpl_extension) = PathBuf::from(tmpl_stem).extension() {
-                        return Ok(tmpl_extension.into());
-                    }
-                }
-            }
-
-            // Get extension from configuration file
-            let usr_cfg_ts = user_new_page_config.typescript.unwrap_or(false);
-            let usr_cfg_jsx = user_new_page_config.jsx.unwrap_or(false);
-            let is_api = matches!(page_type, CreateableFileType::ApiPage);
-
-            if usr_cfg_ts && usr_cfg_jsx && !is_api {
-                Ok("tsx".into())
-            } else if usr_cfg_ts && (!usr_cfg_jsx || is_api) {
-                Ok("ts".into())
-            } else if !usr_cfg_ts && usr_cfg_jsx && !is_api {
-                Ok("jsx".into())
-            } else {
-                Ok("js".into())
-            }
-        } else {
-            Ok(ReactExtension::guess(
-                js_flag,
-                ts_flag,
-                jsx_flag,
-                tsx_flag,
-                None::<UserNewPageConfig>,
-            ))
+            return None;
         }
+
+        Some(ReactExtension::guess(
+            js_flag,
+            ts_flag,
+            jsx_flag,
+            tsx_flag,
+            None::<UserNewPageConfig>,
+        ))
     }
 
     /// If no argument or configuration is set, use the app router by default
@@ -174,24 +257,8 @@ impl<'a> FinalNewPageConfig<'a> {
             true
         } else if app_router_arg {
             false
-        } else if let Some(use_page_router_cfg) = user_new_page_config.page_router {
-            use_page_router_cfg
-        } else {
-            false
-        }
-    }
-
-    fn get_template(
-        template_arg: Option<&String>,
-        user_new_comp_config: &UserNewPageConfig,
-        file_type: &CreateableFileType,
-    ) -> Result<Template<'a>, String> {
-        if let Some(template_name) = template_arg {
-            get_custom_template(template_name, file_type)
-        } else if let Some(template_name) = &user_new_comp_config.template {
-            get_custom_template(template_name, file_type)
         } else {
-            Ok(get_default_template(file_type))
+            user_new_page_config.page_router.unwrap_or_default()
         }
     }
 }
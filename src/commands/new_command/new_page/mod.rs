@@ -3,13 +3,15 @@ use colored::Colorize;
 
 use crate::template::create_from_template;
 
+use super::{explain_template, set_template_args};
+
 use self::final_new_page_config::FinalNewPageConfig;
 
 pub mod final_new_page_config;
 
 /// Sets the new page subcommand
 pub fn set_subcommand(app: Command) -> Command {
-    app.subcommand(
+    app.subcommand(set_template_args(
         Command::new("page")
             .about("Create a new page file, inside /pages/")
             .arg(
@@ -70,13 +72,20 @@ pub fn set_subcommand(app: Command) -> Command {
                     .long("app-router")
                     .action(ArgAction::SetTrue),
             ),
-    )
+    ))
 }
 
 /// Creates a new page based on the given arguments and the configuration file
 pub fn exec_command(cmd_args: &ArgMatches) -> Result<(), String> {
     let page_config = FinalNewPageConfig::new(cmd_args)?;
-    create_from_template(&page_config.page_final_path, page_config.template, &page_config.template_vars)?;
+    explain_template(cmd_args, &page_config.template);
+    create_from_template(
+        &page_config.page_final_path,
+        &page_config.page_type,
+        page_config.template,
+        &page_config.template_vars,
+        page_config.strict_templates,
+    )?;
     println!(
         "Page successfuly created at {}",
         &page_config.page_final_path.to_string_lossy().green()
@@ -1,108 +1,183 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use clap::ArgMatches;
-use path_clean::PathClean;
 
 use crate::{
-    helpers::file_helper,
-    template::{Template, get_custom_template, get_default_template},
+    commands::new_command::{
+        get_destination_config, get_path_arg, get_template, get_template_vars,
+        use_strict_templates,
+    },
+    helpers::project_helper::ProjectDirs,
+    template::{
+        front_matter::FrontMatter, get_default_template, render_path_pattern,
+        render_path_pattern_dir, Template,
+    },
     user_config::{UserConfig, UserNewStyleConfig},
     CreateableFileType,
 };
 
+/// Default path of the new stylesheets, each inside a folder named after it
+const STYLE_PATH_PATTERN: &str = "{{folder}}/{{path}}/{{name}}.{{ext}}";
+
 pub struct FinalNewStyleConfig<'a> {
     /// Where the new component will be located
     pub style_final_path: PathBuf,
     /// Template to be used
     pub template: Template<'a>,
+    pub template_vars: BTreeMap<String, String>,
+    /// Fail if the template uses an undefined variable
+    pub strict_templates: bool,
 }
 
 impl<'a> FinalNewStyleConfig<'a> {
     pub fn new(style_args: &ArgMatches) -> Result<Self, String> {
-        let usr_style_cfg = UserConfig::get()?.get_style_config();
+        let usr_cfg =
+            get_destination_config(|usr_cfg| Self::get_destination_dir(style_args, usr_cfg))?;
+        Self::build(style_args, usr_cfg)
+    }
+
+    fn build(style_args: &ArgMatches, usr_cfg: UserConfig) -> Result<Self, String> {
+        let strict_templates = use_strict_templates(style_args, &usr_cfg);
+        let project_dirs = ProjectDirs::get(&usr_cfg)?;
+        let usr_style_cfg = usr_cfg.to_owned().get_style_config();
 
         // ARGUMENTS
-        let mut path_arg = PathBuf::from(style_args.get_one::<String>("style_name").unwrap());
-        file_helper::rm_double_dots_from_path_buf(&mut path_arg);
-        path_arg = path_arg.clean();
-
-        let style_extension = if let Some(path_arg_extension) = path_arg.extension() {
-            path_arg_extension.to_string_lossy().to_string()
-        } else {
-            match style_args.get_one::<String>("extension") {
-                Some(extension) => extension.to_owned(),
-                None => usr_style_cfg
-                    .extension
-                    .to_owned()
-                    .unwrap_or(String::from("css")),
-            }
-        };
-        let folder = match style_args.get_one::<String>("folder") {
-            Some(folder) => folder.to_owned(),
-            None => usr_style_cfg
-                .folder
-                .to_owned()
-                .unwrap_or(String::from("css")),
-        };
+        let path_arg = get_path_arg(style_args, "style_name");
 
         let file_type = CreateableFileType::Stylesheet;
-        let filestem = path_arg
-            .file_stem()
-            .ok_or("Must specify stylesheet name")?
-            .to_string_lossy();
-        let template = Self::get_template(
-            style_args.get_one::<String>("template"),
-            &usr_style_cfg,
+        let requested_extension = Self::get_requested_extension(style_args, &path_arg);
+        let custom_template = get_template(
+            style_args,
+            usr_style_cfg.template.as_ref(),
             &file_type,
+            requested_extension
+                .as_deref()
+                .or(usr_style_cfg.extension.as_deref()),
+            &usr_cfg,
         )?;
+        let front_matter = match &custom_template {
+            Some(template) => template.load()?.front_matter,
+            None => FrontMatter::default(),
+        };
 
-        let style_final_path =
-            Self::get_style_final_path(&path_arg, &style_extension, &filestem, &folder)?;
+        let style_extension = requested_extension
+            .or_else(|| front_matter.extension.clone())
+            .or_else(|| {
+                custom_template
+                    .as_ref()
+                    .and_then(Template::get_target_extension)
+            })
+            .or_else(|| usr_style_cfg.extension.to_owned())
+            .unwrap_or(String::from("css"));
+        let template = custom_template
+            .unwrap_or_else(|| get_default_template(&file_type, &style_extension));
+
+        let mut template_vars = Self::get_vars(style_args, &path_arg, &usr_style_cfg)?;
+        front_matter.resolve_variables(&mut template_vars)?;
+
+        let style_final_path = render_path_pattern(
+            front_matter
+                .path
+                .as_deref()
+                .or(usr_style_cfg.path.as_deref())
+                .unwrap_or(STYLE_PATH_PATTERN),
+            &template_vars,
+            &project_dirs,
+            &Self::check_destination_dir(Self::get_folder(
+                style_args,
+                &project_dirs,
+                &usr_style_cfg,
+            ))?,
+            &path_arg,
+            &style_extension,
+            strict_templates,
+        )?;
 
         Ok(Self {
             style_final_path,
             template,
+            template_vars,
+            strict_templates,
         })
     }
 
-    fn get_style_final_path(
-        path_arg: &Path,
-        extension: &str,
-        filestem: &str,
-        destination_folder: &str,
+    /// Folder where the configuration creates the stylesheet, before knowing
+    /// its template
+    fn get_destination_dir(
+        style_args: &ArgMatches,
+        usr_cfg: &UserConfig,
     ) -> Result<PathBuf, String> {
-        let path_arg = path_arg.strip_prefix("/").unwrap_or(path_arg).to_path_buf();
-
-        // Base path of the new stylesheet
-        let mut final_path = PathBuf::new();
+        let project_dirs = ProjectDirs::get(usr_cfg)?;
+        let usr_style_cfg = usr_cfg.to_owned().get_style_config();
+        let path_arg = get_path_arg(style_args, "style_name");
+        let style_extension = Self::get_requested_extension(style_args, &path_arg)
+            .or_else(|| usr_style_cfg.extension.to_owned())
+            .unwrap_or(String::from("css"));
+
+        render_path_pattern_dir(
+            usr_style_cfg.path.as_deref().unwrap_or(STYLE_PATH_PATTERN),
+            &Self::get_vars(style_args, &path_arg, &usr_style_cfg)?,
+            &project_dirs,
+            &Self::get_folder(style_args, &project_dirs, &usr_style_cfg),
+            &path_arg,
+            &style_extension,
+        )
+    }
 
-        if file_helper::is_src_present()? {
-            final_path.push("src/");
+    /// Extension requested with the stylesheet's path or the arguments, if
+    /// any
+    fn get_requested_extension(style_args: &ArgMatches, path_arg: &Path) -> Option<String> {
+        match path_arg.extension() {
+            Some(path_arg_extension) => Some(path_arg_extension.to_string_lossy().to_string()),
+            None => style_args.get_one::<String>("extension").cloned(),
         }
+    }
 
-        final_path.push(destination_folder);
-        if !final_path.exists() {
-            return Err(String::from("Couldn't find destination folder"));
-        }
+    /// Variables of the stylesheet's template, besides the ones defined by
+    /// the template itself
+    fn get_vars(
+        style_args: &ArgMatches,
+        path_arg: &Path,
+        usr_style_cfg: &UserNewStyleConfig,
+    ) -> Result<BTreeMap<String, String>, String> {
+        let filestem = path_arg
+            .file_stem()
+            .ok_or("Must specify stylesheet name")?
+            .to_string_lossy();
 
-        final_path.push(path_arg);
-        final_path.push(filestem);
-        final_path.set_extension(extension);
+        get_template_vars(
+            style_args,
+            BTreeMap::from([("name".to_owned(), filestem.to_string())]),
+            usr_style_cfg.variables.as_ref(),
+        )
+    }
 
-        Ok(final_path)
+    /// Folder given as argument or set in the configuration, inside the
+    /// source folder
+    fn get_folder(
+        style_args: &ArgMatches,
+        project_dirs: &ProjectDirs,
+        usr_style_cfg: &UserNewStyleConfig,
+    ) -> PathBuf {
+        let folder = match style_args.get_one::<String>("folder") {
+            Some(folder) => folder.to_owned(),
+            None => usr_style_cfg
+                .folder
+                .to_owned()
+                .unwrap_or(String::from("css")),
+        };
+        project_dirs.src_dir.join(folder)
     }
 
-    fn get_template(
-        template_arg: Option<&String>,
-        user_new_style_config: &UserNewStyleConfig,
-        file_type: &CreateableFileType,
-    ) -> Result<Template<'a>, String> {
-        if let Some(template_name) = template_arg {
-            get_custom_template(template_name, file_type)
-        } else if let Some(template_name) = &user_new_style_config.template {
-            get_custom_template(template_name, file_type)
-        } else {
-            Ok(get_default_template(file_type))
+    /// Checks the folder where stylesheets are created exists
+    fn check_destination_dir(destination_dir: PathBuf) -> Result<PathBuf, String> {
+        if !destination_dir.exists() {
+            return Err(String::from("Couldn't find destination folder"));
         }
+
+        Ok(destination_dir)
     }
 }
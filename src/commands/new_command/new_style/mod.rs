@@ -1,9 +1,9 @@
-use std::collections::BTreeMap;
-
 use clap::{Arg, ArgMatches, Command};
 use colored::Colorize;
 
-use crate::template::create_from_template;
+use crate::{template::create_from_template, CreateableFileType};
+
+use super::{explain_template, set_template_args};
 
 use self::final_new_style_config::FinalNewStyleConfig;
 
@@ -11,7 +11,7 @@ mod final_new_style_config;
 
 /// Sets the new stylesheet subcommand
 pub fn set_subcommand(app: Command) -> Command {
-    app.subcommand(
+    app.subcommand(set_template_args(
         Command::new("style")
             .about("Create a new stylesheet, inside /styles/")
             .arg(Arg::new("style_name").required(true).help(
@@ -34,16 +34,19 @@ pub fn set_subcommand(app: Command) -> Command {
                     .help("The name of your custom template")
                     .long("template"),
             ),
-    )
+    ))
 }
 
 /// Creates a new stylesheet based on the given arguments and the configuration file
 pub fn exec_command(style_args: &ArgMatches) -> Result<(), String> {
     let style_config = FinalNewStyleConfig::new(style_args)?;
+    explain_template(style_args, &style_config.template);
     create_from_template(
         &style_config.style_final_path,
+        &CreateableFileType::Stylesheet,
         style_config.template,
-        &BTreeMap::new()
+        &style_config.template_vars,
+        style_config.strict_templates,
     )?;
     println!(
         "Stylesheet successfuly created at {}",
@@ -1,4 +1,4 @@
-use std::{fs, path::PathBuf};
+use std::fs;
 
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use colored::Colorize;
@@ -7,8 +7,10 @@ use indoc::indoc;
 use crate::{
     helpers::{
         cli_helper::confirm_prompt,
-        file_helper::{self, file_stem_exists, prepend_root_path, rm_file_by_stem},
+        file_helper::{self, file_stem_exists, rm_file_by_stem},
+        project_helper::ProjectDirs,
     },
+    template::provenance::forget_removed_files,
     user_config::UserConfig,
     NextRouter,
 };
@@ -122,12 +124,12 @@ fn rm_page(args: &ArgMatches) -> Result<(), String> {
 }
 
 fn rm_page_from_page_router(page_arg: &str) -> Result<(), String> {
-    let router_dir_name = PathBuf::from("pages/");
-    let mut router_path = prepend_root_path(router_dir_name)?;
+    let mut router_path = ProjectDirs::get(&UserConfig::get()?)?.pages_dir;
 
     if page_arg == "/" {
         router_path.push("index");
-        return file_helper::rm_file_by_stem(router_path);
+        file_helper::rm_file_by_stem(&router_path)?;
+        return forget_removed_files(&router_path);
     }
 
     router_path.push(page_arg);
@@ -144,16 +146,17 @@ fn rm_page_from_page_router(page_arg: &str) -> Result<(), String> {
         return Err(String::from("Operation cancelled."));
     }
 
-    rm_file_by_stem(router_path)
+    rm_file_by_stem(&router_path)?;
+    forget_removed_files(&router_path)
 }
 
 fn rm_page_from_app_router(page_arg: &str) -> Result<(), String> {
-    let router_dir_name = PathBuf::from("app/");
-    let mut router_path = prepend_root_path(router_dir_name)?;
+    let mut router_path = ProjectDirs::get(&UserConfig::get()?)?.app_dir;
 
     if page_arg == "/" {
         router_path.push("page");
-        return file_helper::rm_file_by_stem(router_path);
+        file_helper::rm_file_by_stem(&router_path)?;
+        return forget_removed_files(&router_path);
     }
 
     router_path.push(page_arg);
@@ -169,27 +172,24 @@ fn rm_page_from_app_router(page_arg: &str) -> Result<(), String> {
         return Err(String::from("Operation cancelled."));
     }
 
-    fs::remove_dir_all(router_path).map_err(|err| err.to_string())
+    fs::remove_dir_all(&router_path).map_err(|err| err.to_string())?;
+    forget_removed_files(&router_path)
 }
 
 fn rm_component(args: &ArgMatches) -> Result<(), String> {
     let name_arg = args.get_one::<String>("name").unwrap();
-    let mut comps_folder = prepend_root_path(PathBuf::from(
-        UserConfig::get()?
-            .get_component_config()
-            .folder
-            .unwrap_or(String::from("components")),
-    ))?;
+    let mut comps_folder = ProjectDirs::get(&UserConfig::get()?)?.components_dir;
     comps_folder.push(name_arg);
 
     if !comps_folder.exists() {
         Err(String::from("Target component doesn't exist"))
     } else if comps_folder.is_file() {
-        fs::remove_file(comps_folder).map_err(|_| String::from("Error deleting component's file"))
+        fs::remove_file(&comps_folder).map_err(|_| String::from("Error deleting component's file"))
     } else {
-        fs::remove_dir_all(comps_folder)
+        fs::remove_dir_all(&comps_folder)
             .map_err(|_| String::from("Error deleting component's folder"))
     }?;
+    forget_removed_files(&comps_folder)?;
 
     println!("{}", "Component successfully removed".green());
     Ok(())
@@ -197,22 +197,25 @@ fn rm_component(args: &ArgMatches) -> Result<(), String> {
 
 fn rm_stylesheet(args: &ArgMatches) -> Result<(), String> {
     let name_arg = args.get_one::<String>("name").unwrap();
-    let mut styles_folder = prepend_root_path(PathBuf::from(
-        UserConfig::get()?
+    let usr_cfg = UserConfig::get()?;
+    let mut styles_folder = ProjectDirs::get(&usr_cfg)?.src_dir.join(
+        usr_cfg
             .get_style_config()
             .folder
             .unwrap_or(String::from("styles")),
-    ))?;
+    );
     styles_folder.push(name_arg);
 
     if !styles_folder.exists() {
         Err(String::from("Target stylesheet doesn't exist"))
     } else if styles_folder.is_file() {
-        fs::remove_file(styles_folder).map_err(|_| String::from("Error deleting stylesheet's file"))
+        fs::remove_file(&styles_folder)
+            .map_err(|_| String::from("Error deleting stylesheet's file"))
     } else {
-        fs::remove_dir_all(styles_folder)
+        fs::remove_dir_all(&styles_folder)
             .map_err(|_| String::from("Error deleting stylesheet's folder"))
     }?;
+    forget_removed_files(&styles_folder)?;
 
     println!("{}", "Stylesheet successfully removed".green());
     Ok(())
@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+
+use clap::{Arg, ArgMatches, Command};
+use colored::Colorize;
+
+use crate::{
+    constants::NEXT_BUTLER_DIR,
+    helpers::project_helper,
+    template::{
+        provenance::{
+            diff_generated_file, get_drifted_files, get_generated_file, update_generated_files,
+            FileUpdate,
+        },
+        template_pack::{build_pack, install_pack},
+    },
+    user_config::UserConfig,
+    CreateableFileType,
+};
+
+/// Kinds of generated files, as accepted by --kind
+const KIND_NAMES: [&str; 4] = ["page", "api-page", "component", "stylesheet"];
+
+/// Sets the subcommand and the corresponding arguments
+pub fn set_subcommand(app: Command) -> Command {
+    let template_subcommand = Command::new("template")
+        .about("Manage template packs and the files generated from templates.")
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("install")
+                .about("Install a template pack from a directory or a .tar.gz archive")
+                .after_help(
+                    "Packs are installed inside nextbutler/packs/ and their \
+                    templates are used as --template <pack>/<template>. \n\
+                    Without a source, every pack listed in the template_packs \
+                    configuration is installed.",
+                )
+                .arg(
+                    Arg::new("source")
+                        .help("Directory or .tar.gz archive holding the pack"),
+                ),
+        )
+        .subcommand(
+            Command::new("pack")
+                .about("Build a template pack from the project's templates")
+                .arg(
+                    Arg::new("name")
+                        .help("Name of the pack, used to refer to its templates")
+                        .long("name")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("version")
+                        .help("Version of the pack")
+                        .long("version")
+                        .default_value("0.1.0"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("Path of the archive. Defaults to <name>-<version>.tar.gz")
+                        .long("output"),
+                ),
+        )
+        .subcommand(
+            Command::new("update")
+                .about(
+                    "Re-render the generated files whose template changed, keeping \
+                    the changes made to them",
+                )
+                .after_help(
+                    "Generated files are recorded in nextbutler/manifest.json. \
+                    When the changes made to a file and to its template touch the \
+                    same lines, conflict markers are left in the file.",
+                )
+                .arg(
+                    Arg::new("kind")
+                        .help("Update only the files of this kind")
+                        .long("kind")
+                        .value_parser(KIND_NAMES),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Show the differences between a generated file and its template")
+                .arg(
+                    Arg::new("file")
+                        .help("Path of the generated file")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("drift")
+                .about("List the generated files that don't match their template anymore"),
+        );
+
+    app.subcommand(template_subcommand)
+}
+
+/// Executes the command
+pub fn exec_command(cmd_args: &ArgMatches) -> Result<(), String> {
+    match cmd_args.subcommand() {
+        Some(("install", install_args)) => install(install_args),
+        Some(("pack", pack_args)) => pack(pack_args),
+        Some(("update", update_args)) => update(update_args),
+        Some(("diff", diff_args)) => diff(diff_args),
+        Some(("drift", _)) => drift(),
+        _ => Err(String::from("Unknown command")),
+    }
+}
+
+fn install(args: &ArgMatches) -> Result<(), String> {
+    let sources = match args.get_one::<String>("source") {
+        Some(source) => vec![project_helper::resolve_arg_path(source)
+            .to_string_lossy()
+            .to_string()],
+        None => UserConfig::get()?.template_packs.unwrap_or_default(),
+    };
+
+    if sources.is_empty() {
+        return Err(String::from(
+            "Specify the pack to install or list them in the template_packs configuration",
+        ));
+    }
+
+    for source in sources {
+        println!("Installing template pack from {}...", source);
+        let manifest = install_pack(&source)?;
+        println!(
+            "{}",
+            format!(
+                "Template pack '{}' ({}) successfully installed",
+                manifest.name, manifest.version
+            )
+            .green()
+        );
+    }
+
+    Ok(())
+}
+
+fn pack(args: &ArgMatches) -> Result<(), String> {
+    let name = args.get_one::<String>("name").unwrap();
+    let version = args.get_one::<String>("version").unwrap();
+    let output = match args.get_one::<String>("output") {
+        Some(output) => project_helper::resolve_arg_path(output),
+        None => PathBuf::from(format!("{}-{}.tar.gz", name, version)),
+    };
+
+    let manifest = build_pack(
+        PathBuf::from(NEXT_BUTLER_DIR).join("templates/"),
+        name,
+        version,
+        &output,
+    )?;
+    println!(
+        "{}",
+        format!(
+            "Template pack '{}' ({}) created at {} with: {}",
+            manifest.name,
+            manifest.version,
+            output.display(),
+            manifest.kinds.join(", ")
+        )
+        .green()
+    );
+
+    Ok(())
+}
+
+fn update(args: &ArgMatches) -> Result<(), String> {
+    let kind = match args.get_one::<String>("kind").map(String::as_str) {
+        Some("page") => Some(CreateableFileType::Page),
+        Some("api-page") => Some(CreateableFileType::ApiPage),
+        Some("component") => Some(CreateableFileType::Component),
+        Some("stylesheet") => Some(CreateableFileType::Stylesheet),
+        _ => None,
+    };
+
+    let mut up_to_date = 0;
+    let mut conflicts = 0;
+    for (file, update) in update_generated_files(kind)? {
+        match update {
+            FileUpdate::UpToDate => up_to_date += 1,
+            FileUpdate::Updated => println!("{} {}", "Updated".green(), file),
+            FileUpdate::TemplateChanged => println!(
+                "{} {}: its template changed, but not its content",
+                "Recorded".green(),
+                file
+            ),
+            FileUpdate::Conflicted => {
+                conflicts += 1;
+                println!("{} {}", "Conflicts in".red(), file);
+            }
+            FileUpdate::Skipped(reason) => {
+                println!("{} {}: {}", "Skipped".yellow(), file, reason)
+            }
+        }
+    }
+    println!("{} files were already up to date", up_to_date);
+
+    if conflicts > 0 {
+        return Err(format!(
+            "{} files have conflicts. Resolve the conflict markers before committing them",
+            conflicts
+        ));
+    }
+
+    Ok(())
+}
+
+fn diff(args: &ArgMatches) -> Result<(), String> {
+    let file_path = project_helper::resolve_arg_path(args.get_one::<String>("file").unwrap());
+    let generated_file = get_generated_file(&file_path)?.ok_or(format!(
+        "{} wasn't generated from a template (it's not in nextbutler/manifest.json)",
+        file_path.display()
+    ))?;
+
+    let diff = diff_generated_file(&file_path, &generated_file, console::Term::stdout().is_term())?;
+    if diff.is_empty() {
+        println!("{} matches its template", file_path.display());
+    } else {
+        print!("{}", diff);
+    }
+
+    Ok(())
+}
+
+fn drift() -> Result<(), String> {
+    let drifted_files = get_drifted_files()?;
+    if drifted_files.is_empty() {
+        println!("{}", "Every generated file matches its template".green());
+        return Ok(());
+    }
+
+    for (file, reason) in &drifted_files {
+        println!("{}: {}", file, reason);
+    }
+    Err(format!(
+        "{} generated files don't match their template. Use `nb template diff <file>` to see the differences",
+        drifted_files.len()
+    ))
+}
@@ -5,3 +5,4 @@ pub const COMPONENTS_DEFAULT_FOLDER: &str = "components/";
 
 pub const NEXT_BUTLER_DIR: &str = "nextbutler/";
 pub const CONFIG_FILE_NAME: &str = "nextbutler.json";
+pub const CONFIG_SCHEMA_FILE_NAME: &str = "nextbutler.schema.json";
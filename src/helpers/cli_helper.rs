@@ -1,3 +1,5 @@
+use std::io::IsTerminal;
+
 /// Appends " [y/N]" to prompt, prints it to stdout and returns the answer as a
 /// boolean
 pub fn confirm_prompt(prompt: &str) -> Result<bool, String> {
@@ -9,3 +11,52 @@ pub fn confirm_prompt(prompt: &str) -> Result<bool, String> {
         .map_err(|err| err.to_string())?;
     Ok(answer == 'y' || answer == 'Y')
 }
+
+/// Defines if the tool can ask questions to the user
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && console::Term::stdout().is_term()
+}
+
+/// Prints the prompt to stdout, appending the default value if there is one,
+/// and returns the answer. An empty answer returns the default value
+pub fn input_prompt(prompt: &str, default: Option<&str>) -> Result<Option<String>, String> {
+    match default {
+        Some(default) => println!("{} [{}]", prompt, default),
+        None => println!("{}", prompt),
+    }
+
+    let answer = console::Term::stdout()
+        .read_line()
+        .map_err(|err| err.to_string())?;
+    let answer = answer.trim();
+
+    if answer.is_empty() {
+        Ok(default.map(str::to_owned))
+    } else {
+        Ok(Some(answer.to_owned()))
+    }
+}
+
+/// Prints the prompt and the numbered options to stdout, and returns the
+/// index of the chosen one. An empty answer chooses the first option
+pub fn select_prompt(prompt: &str, options: &[String]) -> Result<usize, String> {
+    println!("{}", prompt);
+    for (index, option) in options.iter().enumerate() {
+        println!("  {}) {}", index + 1, option);
+    }
+
+    loop {
+        let answer = console::Term::stdout()
+            .read_line()
+            .map_err(|err| err.to_string())?;
+        let answer = answer.trim();
+
+        if answer.is_empty() {
+            return Ok(0);
+        }
+        match answer.parse::<usize>() {
+            Ok(chosen) if (1..=options.len()).contains(&chosen) => return Ok(chosen - 1),
+            _ => println!("Choose a number between 1 and {}", options.len()),
+        }
+    }
+}
@@ -4,12 +4,15 @@ use std::{
     ffi::OsStr,
     fs::{self, File},
     io::BufReader,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 use colored::Colorize;
+use path_clean::PathClean;
 use serde::de::DeserializeOwned;
 
+use crate::constants::CRATE_NAME;
+
 pub const FORBIDDEN_FILENAME_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '\"', '<', '>', '|'];
 
 pub fn create(path: &PathBuf, content: Vec<u8>) -> Result<(), String> {
@@ -41,17 +44,6 @@ pub fn create(path: &PathBuf, content: Vec<u8>) -> Result<(), String> {
     Ok(())
 }
 
-/// Defines if the working directory has a src/ folder or not.
-pub fn is_src_present() -> Result<bool, String> {
-    match env::current_dir() {
-        Ok(mut working_dir) => {
-            working_dir.push("src/");
-            Ok(working_dir.exists())
-        }
-        Err(_) => Err(String::from("There was an error finding the src directory")),
-    }
-}
-
 pub fn get_name_or_err(path: &Path) -> Result<&str, String> {
     let file_name = path.file_stem();
 
@@ -170,19 +162,6 @@ pub fn strip_separator(path: PathBuf) -> Result<PathBuf, String> {
     }
 }
 
-/// Prepend "src/" if it's present in the current directory. If it isn't
-/// present this function is a no-op
-pub fn prepend_root_path(path: PathBuf) -> Result<PathBuf, String> {
-    if !is_src_present()? {
-        return Ok(path);
-    }
-
-    let path = strip_separator(path)?;
-    let root_path = PathBuf::from("src/");
-
-    Ok(root_path.join(path))
-}
-
 pub fn file_stem_exists(file_path: impl AsRef<Path>) -> Result<bool, String> {
     let file_path = file_path.as_ref();
 
@@ -265,3 +244,78 @@ pub fn rm_double_dots_from_path_buf(path: &mut PathBuf) {
 
     *path = new_path;
 }
+
+/// Builds the path to `to`, relative to the directory `from_dir`.
+///
+/// Both paths are expected to be relative to the same base (usually the
+/// project root). The result only uses "/" as separator, as it's meant to be
+/// used inside import statements.
+pub fn relative_path(from_dir: impl AsRef<Path>, to: impl AsRef<Path>) -> String {
+    let from_dir = from_dir.as_ref().clean();
+    let to = to.as_ref().clean();
+
+    let from_components: Vec<Component> = from_dir
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect();
+    let to_components: Vec<Component> = to
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect();
+
+    let common_len = from_components
+        .iter()
+        .zip(to_components.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut segments: Vec<String> = vec![String::from(".."); from_components.len() - common_len];
+    segments.extend(
+        to_components[common_len..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+
+    if segments.first().map(String::as_str) != Some("..") {
+        segments.insert(0, String::from("."));
+    }
+
+    segments.join("/")
+}
+
+/// Gets every file inside the given directory and its subdirectories.
+///
+/// Returns an empty vector if the directory doesn't exist
+pub fn get_files_recursively(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, String> {
+    let dir = dir.as_ref();
+    let mut files: Vec<PathBuf> = vec![];
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+
+    for dir_entry in fs::read_dir(dir).map_err(|err| err.to_string())? {
+        let dir_entry_path = dir_entry.map_err(|err| err.to_string())?.path();
+        if dir_entry_path.is_dir() {
+            files.extend(get_files_recursively(&dir_entry_path)?);
+        } else if dir_entry_path.is_file() {
+            files.push(dir_entry_path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The next-butler directory inside the user's configuration directory.
+///
+/// The configuration directory is $XDG_CONFIG_HOME, falling back to
+/// ~/.config (or %APPDATA% on Windows) if it's not defined
+pub fn get_user_config_dir() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|config_home| !config_home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+    Some(config_home.join(CRATE_NAME))
+}
@@ -1,3 +1,4 @@
 pub mod cli_helper;
 pub mod file_helper;
+pub mod project_helper;
 pub mod str_helper;
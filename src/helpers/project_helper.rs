@@ -0,0 +1,302 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use path_clean::PathClean;
+use serde_json::Value;
+
+use crate::{
+    constants::{COMPONENTS_DEFAULT_FOLDER, NEXT_BUTLER_DIR},
+    user_config::{config_source::ConfigSource, read_workspace_config, UserConfig, WorkspaceConfig},
+};
+
+/// Next.js configuration files, which mark the root of a project
+const NEXT_CONFIG_FILE_NAMES: [&str; 4] = [
+    "next.config.js",
+    "next.config.mjs",
+    "next.config.cjs",
+    "next.config.ts",
+];
+
+/// Sections of the package.json where the `next` dependency is searched
+const PACKAGE_JSON_DEPENDENCY_KEYS: [&str; 2] = ["dependencies", "devDependencies"];
+
+/// Directory the tool was run from, before entering the project's root
+static INVOCATION_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Root of the workspace, when the project is one of its apps or packages
+static WORKSPACE_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Whether the directory is the root of a Next.js project: it has the tool's
+/// directory, a Next.js configuration file or a package.json depending on
+/// `next`
+pub fn is_project_root(dir: &Path) -> bool {
+    dir.join(NEXT_BUTLER_DIR).is_dir()
+        || NEXT_CONFIG_FILE_NAMES
+            .iter()
+            .any(|file_name| dir.join(file_name).is_file())
+        || depends_on_next(&dir.join("package.json"))
+}
+
+fn depends_on_next(package_json_path: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(package_json_path) else {
+        return false;
+    };
+    let Ok(package_json) = serde_json::from_str::<Value>(&content) else {
+        return false;
+    };
+
+    PACKAGE_JSON_DEPENDENCY_KEYS
+        .iter()
+        .any(|key| package_json[key].get("next").is_some())
+}
+
+/// Walks up from the given directory to find the project's root
+pub fn find_project_root(start_dir: &Path) -> Option<PathBuf> {
+    start_dir
+        .ancestors()
+        .find(|dir| is_project_root(dir))
+        .map(Path::to_path_buf)
+}
+
+/// Makes the project's root the working directory, so every path is resolved
+/// against it.
+///
+/// The search starts at `root`, `cwd` or the current directory. Inside a
+/// workspace, the root is the folder of the given app or package, or the one
+/// holding the start directory. Otherwise `root` is used as it is, or the
+/// root is searched walking up from the start directory, and if no root is
+/// found the directory itself is used, like for a project that isn't set up
+/// yet
+pub fn enter_project_root(
+    root: Option<&String>,
+    cwd: Option<&String>,
+    app: Option<&String>,
+    package: Option<&String>,
+) -> Result<(), String> {
+    let invocation_dir = env::current_dir()
+        .map_err(|err| format!("Error reading the current directory: {}", err))?;
+
+    let start_dir = match (root, cwd) {
+        (Some(root), _) => invocation_dir.join(root),
+        (None, Some(cwd)) => invocation_dir.join(cwd),
+        (None, None) => invocation_dir.to_owned(),
+    };
+    let project_root = match find_workspace(&start_dir)? {
+        Some((workspace_root, workspace)) => {
+            let project_dir = select_workspace_project(
+                &workspace,
+                &workspace_root,
+                &start_dir.clean(),
+                app,
+                package,
+            )?;
+            let _ = WORKSPACE_ROOT.set(workspace_root.to_owned());
+            workspace_root.join(project_dir)
+        }
+        None if app.is_some() || package.is_some() => {
+            return Err(String::from(
+                "--app and --package can only be used inside a workspace",
+            ))
+        }
+        None if root.is_some() => start_dir,
+        None => find_project_root(&start_dir).unwrap_or(start_dir),
+    };
+    if !project_root.is_dir() {
+        return Err(format!(
+            "The project's root {} is not a directory",
+            project_root.display()
+        ));
+    }
+
+    let _ = INVOCATION_DIR.set(invocation_dir);
+    env::set_current_dir(&project_root).map_err(|err| {
+        format!(
+            "Error entering the project's root {}: {}",
+            project_root.display(),
+            err
+        )
+    })
+}
+
+/// Root of the workspace holding the project, if any
+pub fn get_workspace_root() -> Option<&'static PathBuf> {
+    WORKSPACE_ROOT.get()
+}
+
+/// Walks up from the given directory to find a configuration with a
+/// `workspace` section, returning its root and the section.
+///
+/// Configurations that can't be read are skipped, since they may belong to
+/// unrelated folders above the project. The project's own configuration is
+/// reported when it's loaded
+fn find_workspace(start_dir: &Path) -> Result<Option<(PathBuf, WorkspaceConfig)>, String> {
+    for dir in start_dir.ancestors() {
+        let Some(config_source) = ConfigSource::find(dir).ok().flatten() else {
+            continue;
+        };
+        let Ok(mut config) = config_source.read() else {
+            continue;
+        };
+
+        let workspace = config.get_mut("workspace").map(Value::take);
+        if let Some(workspace) = workspace {
+            let workspace = read_workspace_config(&workspace, &config_source)?;
+            return Ok(Some((dir.to_path_buf(), workspace)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Folder, relative to the workspace's root, of the app or package to work
+/// on: the one given, or the one holding the start directory. If there is
+/// none, the only app of the workspace
+fn select_workspace_project(
+    workspace: &WorkspaceConfig,
+    workspace_root: &Path,
+    start_dir: &Path,
+    app: Option<&String>,
+    package: Option<&String>,
+) -> Result<String, String> {
+    let packages = workspace.packages.to_owned().unwrap_or_default();
+    if let Some(package) = package {
+        return packages.get(package).cloned().ok_or(format!(
+            "Unknown package '{}'. The workspace's packages are: {}",
+            package,
+            get_names(&packages)
+        ));
+    }
+    if let Some(app) = app {
+        return workspace.apps.get(app).cloned().ok_or(format!(
+            "Unknown app '{}'. The workspace's apps are: {}",
+            app,
+            get_names(&workspace.apps)
+        ));
+    }
+
+    let current_project = workspace
+        .apps
+        .values()
+        .chain(packages.values())
+        .find(|project_dir| start_dir.starts_with(workspace_root.join(project_dir).clean()));
+    match (current_project, workspace.apps.values().next()) {
+        (Some(project_dir), _) => Ok(project_dir.to_owned()),
+        (None, Some(app_dir)) if workspace.apps.len() == 1 => Ok(app_dir.to_owned()),
+        _ => Err(format!(
+            "Run the command inside one of the workspace's apps, or choose one with --app: {}",
+            get_names(&workspace.apps)
+        )),
+    }
+}
+
+fn get_names(projects: &BTreeMap<String, String>) -> String {
+    if projects.is_empty() {
+        return String::from("none");
+    }
+    projects.keys().cloned().collect::<Vec<String>>().join(", ")
+}
+
+/// Resolves a path given as argument, which is relative to the directory the
+/// tool was run from, against the project's root. Paths outside of the root
+/// are made absolute
+pub fn resolve_arg_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    let Some(invocation_dir) = INVOCATION_DIR.get() else {
+        return path.to_path_buf();
+    };
+
+    let absolute_path = invocation_dir.join(path).clean();
+    match env::current_dir() {
+        Ok(project_root) => match absolute_path.strip_prefix(&project_root) {
+            Ok(relative_path) if !relative_path.as_os_str().is_empty() => {
+                relative_path.to_path_buf()
+            }
+            Ok(_) => PathBuf::from("."),
+            Err(_) => absolute_path,
+        },
+        Err(_) => absolute_path,
+    }
+}
+
+/// Folders of the Next.js project, relative to its root
+#[derive(Debug, PartialEq)]
+pub struct ProjectDirs {
+    /// Folder holding the routers and components, like "src/". Empty if they
+    /// are at the root
+    pub src_dir: PathBuf,
+    /// Folder of the app router
+    pub app_dir: PathBuf,
+    /// Folder of the pages router
+    pub pages_dir: PathBuf,
+    /// Folder of the static files
+    pub public_dir: PathBuf,
+    /// Folder where components are created
+    pub components_dir: PathBuf,
+}
+
+impl ProjectDirs {
+    /// Gets the folders set in the configuration, guessing the missing ones
+    /// like Next.js does: the routers are inside src/ unless there is an
+    /// app/ or pages/ folder at the root. They must be inside the project
+    pub fn get(user_config: &UserConfig) -> Result<Self, String> {
+        let src_dir = match &user_config.src_dir {
+            Some(src_dir) => PathBuf::from(src_dir),
+            None if Path::new("app/").is_dir() || Path::new("pages/").is_dir() => PathBuf::new(),
+            None if Path::new("src/").is_dir() => PathBuf::from("src/"),
+            None => PathBuf::new(),
+        };
+        let components_dir = match &user_config.components_dir {
+            Some(components_dir) => PathBuf::from(components_dir),
+            None => src_dir.join(
+                user_config
+                    .get_component_folder()
+                    .unwrap_or(COMPONENTS_DEFAULT_FOLDER),
+            ),
+        };
+
+        let project_dirs = Self {
+            app_dir: get_dir_or(&user_config.app_dir, src_dir.join("app/")),
+            pages_dir: get_dir_or(&user_config.pages_dir, src_dir.join("pages/")),
+            public_dir: get_dir_or(&user_config.public_dir, PathBuf::from("public/")),
+            components_dir,
+            src_dir,
+        };
+        for (dir_name, dir) in project_dirs.as_vars() {
+            let dir = PathBuf::from(dir);
+            if dir.is_absolute() || dir.starts_with("..") {
+                return Err(format!(
+                    "The {} {} must be inside the project",
+                    dir_name,
+                    dir.display()
+                ));
+            }
+        }
+
+        Ok(project_dirs)
+    }
+
+    /// The folders as variables of the path patterns, without the trailing
+    /// separator
+    pub fn as_vars(&self) -> [(&'static str, String); 5] {
+        [
+            ("src_dir", &self.src_dir),
+            ("app_dir", &self.app_dir),
+            ("pages_dir", &self.pages_dir),
+            ("public_dir", &self.public_dir),
+            ("components_dir", &self.components_dir),
+        ]
+        .map(|(var_name, dir)| (var_name, get_dir_var(dir)))
+    }
+}
+
+fn get_dir_or(config_dir: &Option<String>, default: PathBuf) -> PathBuf {
+    config_dir.as_ref().map(PathBuf::from).unwrap_or(default)
+}
+
+fn get_dir_var(dir: &Path) -> String {
+    dir.clean().to_string_lossy().replace('\\', "/")
+}
@@ -27,3 +27,136 @@ impl Display for StrHelperError {
         write!(f, "{}", &self.message)
     }
 }
+
+/// Irregular nouns, as (singular, plural) pairs, checked before the regular
+/// pluralisation rules. Singulars ending in "s" are listed too, so they are
+/// not mistaken for plurals
+const IRREGULAR_NOUNS: [(&str, &str); 13] = [
+    ("person", "people"),
+    ("child", "children"),
+    ("woman", "women"),
+    ("man", "men"),
+    ("mouse", "mice"),
+    ("goose", "geese"),
+    ("foot", "feet"),
+    ("tooth", "teeth"),
+    ("datum", "data"),
+    ("status", "statuses"),
+    ("bus", "buses"),
+    ("virus", "viruses"),
+    ("alias", "aliases"),
+];
+
+/// Nouns that are the same in singular and plural
+const UNCOUNTABLE_NOUNS: [&str; 8] = [
+    "data",
+    "information",
+    "news",
+    "series",
+    "species",
+    "settings",
+    "equipment",
+    "metadata",
+];
+
+/// Returns the english plural of the last word of the given string.
+///
+/// Only the last word is changed, so "UserProfile" becomes "UserProfiles"
+pub fn pluralize(word: &str) -> String {
+    if word.is_empty() || ends_with_word(word, &UNCOUNTABLE_NOUNS) {
+        return word.to_owned();
+    }
+
+    for (singular, plural) in IRREGULAR_NOUNS {
+        if let Some(replaced) = replace_last_word(word, singular, plural) {
+            return replaced;
+        }
+    }
+
+    let lower = word.to_lowercase();
+    if lower.ends_with('y') && !ends_with_vowel_and(&lower, 'y') {
+        format!("{}ies", &word[..word.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+    {
+        format!("{}es", word)
+    } else {
+        format!("{}s", word)
+    }
+}
+
+/// Returns the english singular of the last word of the given string.
+///
+/// Only the last word is changed, so "UserProfiles" becomes "UserProfile"
+pub fn singularize(word: &str) -> String {
+    if word.is_empty() || ends_with_word(word, &UNCOUNTABLE_NOUNS) {
+        return word.to_owned();
+    }
+
+    for (singular, plural) in IRREGULAR_NOUNS {
+        if let Some(replaced) = replace_last_word(word, plural, singular) {
+            return replaced;
+        }
+    }
+    if ends_with_word(word, &IRREGULAR_NOUNS.map(|(singular, _)| singular)) {
+        return word.to_owned();
+    }
+
+    let lower = word.to_lowercase();
+    if lower.ends_with("ies") && lower.len() > 3 {
+        format!("{}y", &word[..word.len() - 3])
+    } else if ["sses", "xes", "zes", "ches", "shes"]
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+    {
+        word[..word.len() - 2].to_owned()
+    } else if lower.ends_with('s') && !lower.ends_with("ss") {
+        word[..word.len() - 1].to_owned()
+    } else {
+        word.to_owned()
+    }
+}
+
+fn ends_with_vowel_and(lower_word: &str, last: char) -> bool {
+    let mut chars = lower_word.chars().rev();
+    chars.next() == Some(last) && matches!(chars.next(), Some('a' | 'e' | 'i' | 'o' | 'u'))
+}
+
+/// Finds where the last word of the string begins. Words are delimited by
+/// separators or by an uppercase letter (as in PascalCase or camelCase)
+fn last_word_start(word: &str) -> usize {
+    word.char_indices()
+        .rev()
+        .find(|(_, ch)| ch.is_uppercase() || VALID_SEPARATORS.contains(ch) || *ch == ' ')
+        .map(|(idx, ch)| if ch.is_uppercase() { idx } else { idx + ch.len_utf8() })
+        .unwrap_or(0)
+}
+
+fn ends_with_word(word: &str, candidates: &[&str]) -> bool {
+    let last_word = word[last_word_start(word)..].to_lowercase();
+    candidates.contains(&last_word.as_str())
+}
+
+/// Replaces the last word of `word` by `to` if it equals `from`, keeping the
+/// capitalization of its first letter
+fn replace_last_word(word: &str, from: &str, to: &str) -> Option<String> {
+    let start = last_word_start(word);
+    let (head, last_word) = word.split_at(start);
+    if last_word.to_lowercase() != from {
+        return None;
+    }
+
+    let capitalized = last_word.chars().next().is_some_and(char::is_uppercase);
+    let to = if capitalized {
+        let mut chars = to.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    } else {
+        to.to_owned()
+    };
+
+    Some(format!("{}{}", head, to))
+}
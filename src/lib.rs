@@ -7,11 +7,16 @@ pub mod user_config;
 
 use std::{env, process};
 
-use clap::Command;
+use clap::{Arg, ArgMatches, Command};
 use colored::Colorize;
-use commands::{init_command, new_command, rm_command};
+use commands::{config_command, init_command, new_command, rm_command, template_command};
 use constants::{CRATE_NAME, CRATE_VERSION};
+use helpers::project_helper;
+use serde::{Deserialize, Serialize};
+use user_config::config_layers;
 
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum CreateableFileType {
     Page,
     ApiPage,
@@ -29,13 +34,30 @@ pub fn run() {
     let app = new_command::set_subcommand(base_cmd);
     let app = init_command::set_subcommand(app);
     let app = rm_command::set_subcommand(app);
+    let app = template_command::set_subcommand(app);
+    let app = config_command::set_subcommand(app);
 
     let base_cmd_args_matches = app.get_matches();
 
+    if let Err(err) = project_helper::enter_project_root(
+        get_global_arg(&base_cmd_args_matches, "root"),
+        get_global_arg(&base_cmd_args_matches, "cwd"),
+        get_global_arg(&base_cmd_args_matches, "app"),
+        get_global_arg(&base_cmd_args_matches, "package"),
+    ) {
+        eprintln!("{}", err.red());
+        process::exit(1);
+    }
+    if let Some(preset) = get_global_arg(&base_cmd_args_matches, "preset") {
+        config_layers::select_preset(preset);
+    }
+
     let command_result = match base_cmd_args_matches.subcommand() {
         Some(("new", cmd_args)) => new_command::exec_command(cmd_args),
         Some(("init", _)) => init_command::exec_command(),
         Some(("rm", cmd_args)) => rm_command::exec_command(cmd_args),
+        Some(("template", cmd_args)) => template_command::exec_command(cmd_args),
+        Some(("config", cmd_args)) => config_command::exec_command(cmd_args),
         _ => Err(String::from("Unknown command")),
     };
 
@@ -56,6 +78,44 @@ pub fn build_base_cmd() -> Command {
         )
         .version(CRATE_VERSION)
         .arg_required_else_help(true)
+        .arg(
+            Arg::new("root")
+                .long("root")
+                .value_name("DIR")
+                .global(true)
+                .help("Root of the project. By default, it's searched walking up from the current directory"),
+        )
+        .arg(
+            Arg::new("cwd")
+                .long("cwd")
+                .value_name("DIR")
+                .global(true)
+                .conflicts_with("root")
+                .help("Run as if the tool was started in the given directory"),
+        )
+        .arg(
+            Arg::new("app")
+                .long("app")
+                .value_name("NAME")
+                .global(true)
+                .help("App of the workspace to work on. By default, the one holding the current directory"),
+        )
+        .arg(
+            Arg::new("preset")
+                .long("preset")
+                .value_name("NAME")
+                .global(true)
+                .help("Preset of the configuration to apply over the base one"),
+        )
+}
+
+/// Gets the value of a global argument, or of an argument of the last
+/// subcommand, which is where clap stores them
+fn get_global_arg<'a>(matches: &'a ArgMatches, arg_id: &str) -> Option<&'a String> {
+    match matches.subcommand() {
+        Some((_, sub_matches)) => get_global_arg(sub_matches, arg_id),
+        None => matches.try_get_one::<String>(arg_id).ok().flatten(),
+    }
 }
 
 /// The path of the directory where the binary is compiled to
@@ -4,6 +4,7 @@ pub trait GuessReactExtension {
     fn guess_extension(&self) -> ReactExtension;
 }
 
+#[derive(Clone, Copy)]
 pub enum ReactExtension {
     Jsx,
     Tsx,
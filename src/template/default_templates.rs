@@ -7,12 +7,72 @@ pub const DEFAULT_PAGE_TEMPLATE: &str = r#"export default function {{ name }}()
 }
 "#;
 
+pub const DEFAULT_TSX_PAGE_TEMPLATE: &str = r#"// Props passed to the pages of the app router
+export type {{ name }}Props = {
+  params: Promise<Record<string, string | string[]>>;
+  searchParams: Promise<Record<string, string | string[] | undefined>>;
+};
+
+export default function {{ name }}() {
+  return (
+    <div>
+      <h1>\o/</h1>
+    </div>
+  );
+}
+"#;
+
+// .ts files can't contain JSX
+pub const DEFAULT_TS_PAGE_TEMPLATE: &str = r#"import { createElement, type ReactElement } from "react";
+
+// Props passed to the pages of the app router
+export type {{ name }}Props = {
+  params: Promise<Record<string, string | string[]>>;
+  searchParams: Promise<Record<string, string | string[] | undefined>>;
+};
+
+export default function {{ name }}(): ReactElement {
+  return createElement("div", null, createElement("h1", null, "\\o/"));
+}
+"#;
+
+pub const DEFAULT_TSX_PAGE_ROUTER_TEMPLATE: &str = r#"import type { ReactElement } from "react";
+
+export default function {{ name }}(): ReactElement {
+  return (
+    <div>
+      <h1>\o/</h1>
+    </div>
+  );
+}
+"#;
+
+// .ts files can't contain JSX
+pub const DEFAULT_TS_PAGE_ROUTER_TEMPLATE: &str = r#"import { createElement, type ReactElement } from "react";
+
+export default function {{ name }}(): ReactElement {
+  return createElement("div", null, createElement("h1", null, "\\o/"));
+}
+"#;
+
 pub const DEFAULT_API_PAGE_TEMPLATE: &str = r#"// Next.js API route support: https://nextjs.org/docs/api-routes/introduction
 
 export default function handler(req, res) {
   res.status(200).json({ name: 'John Doe' })
 }"#;
 
+pub const DEFAULT_TS_API_PAGE_TEMPLATE: &str = r#"// Next.js API route support: https://nextjs.org/docs/api-routes/introduction
+import type { NextApiRequest, NextApiResponse } from "next";
+
+type Data = {
+  name: string;
+};
+
+export default function handler(req: NextApiRequest, res: NextApiResponse<Data>) {
+  res.status(200).json({ name: "John Doe" });
+}
+"#;
+
 pub const DEFAULT_COMPONENT_TEMPLATE: &str = r#"export default function {{ name }}() {
     return (
         <div>
@@ -22,7 +82,51 @@ pub const DEFAULT_COMPONENT_TEMPLATE: &str = r#"export default function {{ name
 }
 "#;
 
+pub const DEFAULT_TSX_COMPONENT_TEMPLATE: &str = r#"import type { ReactNode } from "react";
+
+type {{ name }}Props = {
+  children?: ReactNode;
+};
+
+export default function {{ name }}({ children }: {{ name }}Props) {
+  return (
+    <div>
+      <h1>Hi! \o/</h1>
+      {children}
+    </div>
+  );
+}
+"#;
+
+// .ts files can't contain JSX
+pub const DEFAULT_TS_COMPONENT_TEMPLATE: &str = r#"import { createElement, type ReactElement, type ReactNode } from "react";
+
+type {{ name }}Props = {
+  children?: ReactNode;
+};
+
+export default function {{ name }}({ children }: {{ name }}Props): ReactElement {
+  return createElement("div", null, createElement("h1", null, "Hi! \\o/"), children);
+}
+"#;
+
 pub const DEFAULT_STYLESHEET_TEMPLATE: &str = r#"html, body {
     background: red;
 }
 "#;
+
+/// Every built-in template as a custom one, with its path relative to the
+/// templates directory. Used to create the example templates
+pub const DEFAULT_TEMPLATE_FILES: [(&str, &str); 11] = [
+    ("pages/default.jsx.hbs", DEFAULT_PAGE_TEMPLATE),
+    ("pages/default.js.hbs", DEFAULT_PAGE_TEMPLATE),
+    ("pages/default.tsx.hbs", DEFAULT_TSX_PAGE_TEMPLATE),
+    ("pages/default.ts.hbs", DEFAULT_TS_PAGE_TEMPLATE),
+    ("api-pages/default.js.hbs", DEFAULT_API_PAGE_TEMPLATE),
+    ("api-pages/default.ts.hbs", DEFAULT_TS_API_PAGE_TEMPLATE),
+    ("components/default.jsx.hbs", DEFAULT_COMPONENT_TEMPLATE),
+    ("components/default.js.hbs", DEFAULT_COMPONENT_TEMPLATE),
+    ("components/default.tsx.hbs", DEFAULT_TSX_COMPONENT_TEMPLATE),
+    ("components/default.ts.hbs", DEFAULT_TS_COMPONENT_TEMPLATE),
+    ("stylesheets/default.css.hbs", DEFAULT_STYLESHEET_TEMPLATE),
+];
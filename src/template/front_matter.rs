@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::helpers::cli_helper;
+
+use super::template_variables::TemplateVars;
+
+const YAML_DELIMITER: &str = "---";
+const TOML_DELIMITER: &str = "+++";
+
+/// Optional block at the top of a template, delimited by "---" (YAML) or
+/// "+++" (TOML), that describes how the template has to be used
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FrontMatter {
+    /// Extension of the generated file
+    pub extension: Option<String>,
+    /// Pattern of the generated file's path, relative to the project's root
+    pub path: Option<String>,
+    /// Variables used by the template
+    #[serde(default)]
+    pub variables: BTreeMap<String, FrontMatterVariable>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FrontMatterVariable {
+    /// What the variable is used for
+    pub description: Option<String>,
+    /// Value to use if the variable is not defined. Numbers and booleans are
+    /// used as strings
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub default: Option<String>,
+    /// Fail if the variable is not defined and it has no default value
+    #[serde(default)]
+    pub required: bool,
+    /// Question to ask when the variable is not defined
+    pub prompt: Option<String>,
+}
+
+/// Reads a string, number or boolean as a string, so defaults like
+/// `default: 3` don't need quotes
+fn deserialize_scalar<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged, expecting = "the default value must be a string, number or boolean")]
+    enum Scalar {
+        String(String),
+        Bool(bool),
+        Integer(i64),
+        Float(f64),
+    }
+
+    Ok(Option::<Scalar>::deserialize(deserializer)?.map(|scalar| match scalar {
+        Scalar::String(value) => value,
+        Scalar::Bool(value) => value.to_string(),
+        Scalar::Integer(value) => value.to_string(),
+        Scalar::Float(value) => value.to_string(),
+    }))
+}
+
+/// Content of a template, split from its front-matter
+pub struct TemplateSource {
+    pub front_matter: FrontMatter,
+    pub body: String,
+    /// Amount of lines taken by the front-matter, used to report errors at
+    /// the right line of the template file
+    pub body_line_offset: usize,
+}
+
+impl TemplateSource {
+    /// Splits the front-matter from the content of a template. Templates
+    /// without front-matter are returned as is
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let first_line = content.lines().next().unwrap_or_default().trim_end();
+        if first_line != YAML_DELIMITER && first_line != TOML_DELIMITER {
+            return Ok(Self {
+                front_matter: FrontMatter::default(),
+                body: content.to_owned(),
+                body_line_offset: 0,
+            });
+        }
+
+        let mut lines = content.split_inclusive('\n');
+        lines.next();
+
+        let mut raw_front_matter = String::new();
+        let mut body_line_offset = 1;
+        let mut closed = false;
+        for line in lines.by_ref() {
+            body_line_offset += 1;
+            if line.trim_end() == first_line {
+                closed = true;
+                break;
+            }
+            raw_front_matter.push_str(line);
+        }
+
+        if !closed {
+            return Err(format!(
+                "The template's front-matter must be closed with '{}'",
+                first_line
+            ));
+        }
+
+        let front_matter = if first_line == YAML_DELIMITER {
+            serde_yaml::from_str::<Option<FrontMatter>>(&raw_front_matter)
+                .map(Option::unwrap_or_default)
+                .map_err(|err| format!("Invalid template front-matter: {}", err))?
+        } else {
+            toml::from_str(&raw_front_matter)
+                .map_err(|err| format!("Invalid template front-matter: {}", err))?
+        };
+
+        Ok(Self {
+            front_matter,
+            body: lines.collect(),
+            body_line_offset,
+        })
+    }
+}
+
+impl FrontMatter {
+    /// Fills the variables declared in the front-matter that are missing from
+    /// `template_vars`.
+    ///
+    /// Variables with a prompt are asked interactively, using the default
+    /// value if the answer is empty. Otherwise the default value is used.
+    /// Returns an error if a required variable ends up without a value
+    pub fn resolve_variables(&self, template_vars: &mut TemplateVars) -> Result<(), String> {
+        for (var_name, variable) in &self.variables {
+            if template_vars.contains_key(var_name) {
+                continue;
+            }
+
+            let value = match &variable.prompt {
+                Some(prompt) if cli_helper::is_interactive() => {
+                    cli_helper::input_prompt(prompt, variable.default.as_deref())?
+                }
+                _ => variable.default.clone(),
+            };
+
+            match value {
+                Some(value) => {
+                    template_vars.insert(var_name.to_owned(), value);
+                }
+                None if variable.required => {
+                    return Err(match &variable.description {
+                        Some(description) => format!(
+                            "Missing required template variable '{}' ({}). Define it with --var {}=<value>",
+                            var_name, description, var_name
+                        ),
+                        None => format!(
+                            "Missing required template variable '{}'. Define it with --var {}=<value>",
+                            var_name, var_name
+                        ),
+                    });
+                }
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Helpers available to every template.
+//!
+//! Besides the ones defined here, handlebars already provides `eq`, `ne`,
+//! `and`, `or`, `not`, `gt`, `gte`, `lt`, `lte` and `len` by default. MiniJinja
+//! templates get the ones defined here as filters and as functions.
+
+use std::path::{Path, PathBuf};
+
+use convert_case::{Case, Casing};
+use handlebars::{handlebars_helper, Handlebars};
+
+use crate::helpers::{file_helper, str_helper};
+
+/// Extensions that are omitted when building an import path
+const IMPORT_OMITTED_EXTENSIONS: [&str; 4] = ["js", "jsx", "ts", "tsx"];
+
+/// Helper transforming a single string
+type StrHelper = fn(&str) -> String;
+
+pub fn to_pascal(value: &str) -> String {
+    value.to_case(Case::Pascal)
+}
+
+pub fn to_camel(value: &str) -> String {
+    value.to_case(Case::Camel)
+}
+
+pub fn to_kebab(value: &str) -> String {
+    value.to_case(Case::Kebab)
+}
+
+pub fn to_snake(value: &str) -> String {
+    value.to_case(Case::Snake)
+}
+
+pub fn to_upper(value: &str) -> String {
+    value.to_uppercase()
+}
+
+pub fn to_plural(value: &str) -> String {
+    str_helper::pluralize(value)
+}
+
+pub fn to_singular(value: &str) -> String {
+    str_helper::singularize(value)
+}
+
+/// Builds the path to use when importing `to` from the file `from`.
+///
+/// Script extensions are omitted, as the bundler resolves them.
+pub fn relative_import(from: &str, to: &str) -> String {
+    let from_dir = Path::new(from).parent().unwrap_or(Path::new(""));
+    let mut to = PathBuf::from(to);
+    let omit_extension = to
+        .extension()
+        .is_some_and(|ext| IMPORT_OMITTED_EXTENSIONS.contains(&ext.to_string_lossy().as_ref()));
+    if omit_extension {
+        to.set_extension("");
+    }
+
+    file_helper::relative_path(from_dir, to)
+}
+
+handlebars_helper!(pascal: |value: str| to_pascal(value));
+handlebars_helper!(camel: |value: str| to_camel(value));
+handlebars_helper!(kebab: |value: str| to_kebab(value));
+handlebars_helper!(snake: |value: str| to_snake(value));
+handlebars_helper!(upper: |value: str| to_upper(value));
+handlebars_helper!(plural: |value: str| to_plural(value));
+handlebars_helper!(singular: |value: str| to_singular(value));
+handlebars_helper!(relative_import_helper: |from: str, to: str| relative_import(from, to));
+
+/// Names of the next-butler helpers, used to suggest them in errors
+pub const HELPER_NAMES: [&str; 8] = [
+    "pascal",
+    "camel",
+    "kebab",
+    "snake",
+    "upper",
+    "plural",
+    "singular",
+    "relative_import",
+];
+
+/// Registers all the next-butler helpers in the given registry
+pub fn register_helpers(handlebars: &mut Handlebars) {
+    handlebars.register_helper("pascal", Box::new(pascal));
+    handlebars.register_helper("camel", Box::new(camel));
+    handlebars.register_helper("kebab", Box::new(kebab));
+    handlebars.register_helper("snake", Box::new(snake));
+    handlebars.register_helper("upper", Box::new(upper));
+    handlebars.register_helper("plural", Box::new(plural));
+    handlebars.register_helper("singular", Box::new(singular));
+    handlebars.register_helper("relative_import", Box::new(relative_import_helper));
+}
+
+/// Registers all the next-butler helpers in the given MiniJinja environment,
+/// both as filters (`{{ name | pascal }}`) and as functions
+/// (`{{ pascal(name) }}`)
+pub fn register_jinja_helpers(env: &mut minijinja::Environment) {
+    let helpers: [(&str, StrHelper); 7] = [
+        ("pascal", to_pascal),
+        ("camel", to_camel),
+        ("kebab", to_kebab),
+        ("snake", to_snake),
+        ("upper", to_upper),
+        ("plural", to_plural),
+        ("singular", to_singular),
+    ];
+    for (helper_name, helper) in helpers {
+        env.add_filter(helper_name, move |value: &str| helper(value));
+        env.add_function(helper_name, move |value: &str| helper(value));
+    }
+
+    env.add_filter("relative_import", |from: &str, to: &str| relative_import(from, to));
+    env.add_function("relative_import", |from: &str, to: &str| relative_import(from, to));
+}
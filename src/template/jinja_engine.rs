@@ -0,0 +1,39 @@
+use minijinja::{AutoEscape, Environment, UndefinedBehavior};
+
+use super::{
+    helpers::register_jinja_helpers,
+    template_error::{TemplateError, TemplateErrorSources},
+    template_variables::TemplateVars,
+};
+
+/// Renders a MiniJinja template (.jinja or .j2) registered in `sources`,
+/// which can include the given partials with `{% include "name" %}`.
+///
+/// When `strict` is true, using a variable that is not defined fails instead
+/// of rendering an empty string
+pub fn render_jinja(
+    template_name: &str,
+    partial_names: &[String],
+    sources: &TemplateErrorSources,
+    template_vars: &TemplateVars,
+    strict: bool,
+) -> Result<String, TemplateError> {
+    let mut env = Environment::new();
+    // Generated files are source code, so nothing is escaped and they keep
+    // their last line break
+    env.set_auto_escape_callback(|_| AutoEscape::None);
+    env.set_keep_trailing_newline(true);
+    if strict {
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+    }
+    register_jinja_helpers(&mut env);
+
+    for tmpl_name in partial_names.iter().map(String::as_str).chain([template_name]) {
+        env.add_template_owned(tmpl_name.to_owned(), sources[tmpl_name].content.to_owned())
+            .map_err(|err| TemplateError::from_jinja_error(&err, sources, template_vars))?;
+    }
+
+    env.get_template(template_name)
+        .and_then(|template| template.render(template_vars))
+        .map_err(|err| TemplateError::from_jinja_error(&err, sources, template_vars))
+}
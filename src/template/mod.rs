@@ -1,180 +1,812 @@
 use std::{
     collections::BTreeMap,
+    ffi::OsStr,
     fs::{self, File},
+    io::Write,
     path::{Path, PathBuf},
 };
 
-use handlebars::Handlebars;
+use handlebars::{
+    Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderErrorReason,
+};
+
+use path_clean::PathClean;
 
 use crate::{
     constants::NEXT_BUTLER_DIR,
-    helpers::file_helper::{self, get_file_stem_occurrences},
-    CreateableFileType,
+    helpers::{cli_helper, file_helper, project_helper::ProjectDirs},
+    CreateableFileType, NextRouter,
+};
+
+use self::{
+    front_matter::TemplateSource,
+    output_format::format_output,
+    provenance::{GeneratedFile, Provenance},
+    template_layer::{get_kind_dir_name, get_template_layers, TemplateLayer},
+    template_error::{TemplateError, TemplateErrorSource, TemplateErrorSources},
+    template_pack::{get_installed_pack, get_installed_packs},
+    template_variables::TemplateVars,
 };
 
 use self::default_templates::{
     DEFAULT_API_PAGE_TEMPLATE, DEFAULT_COMPONENT_TEMPLATE, DEFAULT_PAGE_TEMPLATE,
-    DEFAULT_STYLESHEET_TEMPLATE,
+    DEFAULT_STYLESHEET_TEMPLATE, DEFAULT_TEMPLATE_FILES, DEFAULT_TSX_COMPONENT_TEMPLATE,
+    DEFAULT_TSX_PAGE_ROUTER_TEMPLATE, DEFAULT_TSX_PAGE_TEMPLATE, DEFAULT_TS_API_PAGE_TEMPLATE,
+    DEFAULT_TS_COMPONENT_TEMPLATE, DEFAULT_TS_PAGE_ROUTER_TEMPLATE, DEFAULT_TS_PAGE_TEMPLATE,
 };
 
 pub mod default_templates;
+pub mod front_matter;
+pub mod helpers;
+pub mod jinja_engine;
+pub mod output_format;
+pub mod provenance;
+pub mod template_error;
+pub mod template_layer;
+pub mod template_pack;
 pub mod template_variables;
 
+/// Name that refers to the built-in template when there is no custom
+/// template overriding it
+pub const DEFAULT_TEMPLATE_NAME: &str = "default";
+
+/// Extensions of the template files, and the engine that renders them
+const TEMPLATE_FILE_EXTENSIONS: [(&str, TemplateEngine); 3] = [
+    (".hbs", TemplateEngine::Handlebars),
+    (".jinja", TemplateEngine::Jinja),
+    (".j2", TemplateEngine::Jinja),
+];
+
+/// Languages the templates can be written in, chosen by the template's file
+/// extension
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TemplateEngine {
+    /// .hbs templates, like the built-in ones
+    Handlebars,
+    /// .jinja and .j2 templates, rendered with MiniJinja
+    Jinja,
+}
+
+/// Splits the template's extension from a file name, returning the name
+/// without it and the engine that renders the file. `None` if it's not a
+/// template file
+fn split_template_extension(file_name: &str) -> Option<(&str, TemplateEngine)> {
+    TEMPLATE_FILE_EXTENSIONS
+        .iter()
+        .find_map(|(tmpl_extension, engine)| {
+            file_name
+                .strip_suffix(tmpl_extension)
+                .map(|file_name| (file_name, *engine))
+        })
+}
+
+/// Folder, inside every template layer, holding the partials
+const PARTIALS_DIR_NAME: &str = "partials/";
+
+/// Extensions of the files that collide with each other when they share a
+/// name, since they would be imported the same way
+const SCRIPT_EXTENSIONS: [&str; 4] = ["js", "jsx", "ts", "tsx"];
+
 pub enum Template<'a> {
     // Content of the template, used only when loading default templates
     Str(&'a str),
-    // Path to the template, used when loading custom templates
-    Path(PathBuf),
+    // Path to the template and the layer it was found in, used when loading
+    // custom templates
+    Path(PathBuf, TemplateLayer),
+}
+
+impl<'a> Template<'a> {
+    /// Name used to refer to the template in messages
+    pub fn get_display_name(&self) -> String {
+        match self {
+            Template::Str(_) => String::from("built-in template"),
+            Template::Path(tmpl_path, _) => tmpl_path.display().to_string(),
+        }
+    }
+
+    /// Extension of the file to create, if the template's name defines it
+    /// like "card.tsx.hbs"
+    pub fn get_target_extension(&self) -> Option<String> {
+        match self {
+            Template::Str(_) => None,
+            Template::Path(tmpl_path, _) => get_template_target_extension(tmpl_path),
+        }
+    }
+
+    /// Engine that renders the template
+    pub fn get_engine(&self) -> TemplateEngine {
+        match self {
+            Template::Str(_) => TemplateEngine::Handlebars,
+            Template::Path(tmpl_path, _) => tmpl_path
+                .file_name()
+                .and_then(|file_name| {
+                    split_template_extension(&file_name.to_string_lossy()).map(|(_, engine)| engine)
+                })
+                .unwrap_or(TemplateEngine::Handlebars),
+        }
+    }
+
+    pub fn is_built_in(&self) -> bool {
+        matches!(self, Template::Str(_))
+    }
+
+    /// Layer the template was found in
+    pub fn get_layer(&self) -> TemplateLayer {
+        match self {
+            Template::Str(_) => TemplateLayer::BuiltIn,
+            Template::Path(_, layer) => layer.to_owned(),
+        }
+    }
+
+    /// Reads the whole content of the template, front-matter included
+    pub fn read(&self) -> Result<String, String> {
+        match self {
+            Template::Str(tmpl_content) => Ok(tmpl_content.to_string()),
+            Template::Path(tmpl_path, _) => fs::read_to_string(tmpl_path).map_err(|err| {
+                format!("Error reading template {}: {}", tmpl_path.display(), err)
+            }),
+        }
+    }
+
+    /// Reads the template, splitting its front-matter from its body
+    pub fn load(&self) -> Result<TemplateSource, String> {
+        let tmpl_content = self.read()?;
+        match self {
+            Template::Str(_) => TemplateSource::parse(&tmpl_content),
+            Template::Path(tmpl_path, _) => TemplateSource::parse(&tmpl_content)
+                .map_err(|err| format!("{} ({})", err, tmpl_path.display())),
+        }
+    }
 }
 
+/// Searches a custom template by its name through every template layer. The
+/// first layer containing it is used.
+///
+/// The name can include the template's extension ("card.tsx") and its parent
+/// folders ("cards/card"). Without the extension, it matches both "card.hbs"
+/// and "card.<extension>.hbs". If a layer has more than one of them, the one
+/// for `extension` is used, or the first one in `extension_order` (the
+/// `template_extension_order` configuration). See [choose_template_variant]
+///
+/// Names starting with the name of an installed pack ("acme/card") are
+/// searched only inside that pack.
+///
+/// If a template named "default" is not found, `None` is returned, meaning
+/// that the built-in template has to be used. See [get_default_template]
 pub fn get_custom_template<'a>(
     template_name: &str,
     file_type_to_create: &CreateableFileType,
-) -> Result<Template<'a>, String> {
+    extension: Option<&str>,
+    extension_order: &[String],
+) -> Result<Option<Template<'a>>, String> {
     let template_arg_path = PathBuf::from(template_name);
-    let custom_templates_dir = get_custom_templates_dir(file_type_to_create)?;
-
-    // If the user specified the custom template extension, directly search the
-    // file
-    if let Some(tmpl_arg_extension) = template_arg_path.extension() {
-        let mut tmpl_arg_extension = tmpl_arg_extension.to_owned();
-        tmpl_arg_extension.push(".hbs");
-
-        let mut tmpl_full_path = custom_templates_dir.join(template_arg_path);
-        tmpl_full_path.set_extension(tmpl_arg_extension);
-        if tmpl_full_path.is_file() {
-            return Ok(Template::Path(tmpl_full_path));
-        } else {
-            return Err(String::from(
-                "Couldn't find a template with the given extension",
-            ));
+    if let Some(pack_template) =
+        get_pack_template(&template_arg_path, file_type_to_create, extension, extension_order)?
+    {
+        return Ok(Some(pack_template));
+    }
+
+    let layers = get_template_layers()?;
+
+    for (layer, layer_dir) in &layers {
+        let kind_dir = layer_dir.join(get_kind_dir_name(file_type_to_create));
+        if let Some(tmpl_path) =
+            find_template_in_dir(&template_arg_path, &kind_dir, extension, extension_order)?
+        {
+            return Ok(Some(Template::Path(tmpl_path, layer.to_owned())));
+        }
+    }
+
+    if template_name == DEFAULT_TEMPLATE_NAME {
+        return Ok(None);
+    }
+
+    let searched_dirs = layers
+        .iter()
+        .map(|(_, layer_dir)| {
+            layer_dir
+                .join(get_kind_dir_name(file_type_to_create))
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    Err(format!(
+        "Couldn't find the template '{}'. Searched in: {}",
+        template_name, searched_dirs
+    ))
+}
+
+/// Searches the template inside an installed pack, if the template's name
+/// starts with the name of one
+fn get_pack_template<'a>(
+    template_arg_path: &Path,
+    file_type_to_create: &CreateableFileType,
+    extension: Option<&str>,
+    extension_order: &[String],
+) -> Result<Option<Template<'a>>, String> {
+    let mut components = template_arg_path.components();
+    let (Some(pack_name), rest) = (components.next(), components.as_path()) else {
+        return Ok(None);
+    };
+    if rest.as_os_str().is_empty() {
+        return Ok(None);
+    }
+
+    let pack_name = pack_name.as_os_str().to_string_lossy();
+    let Some((manifest, pack_dir)) = get_installed_pack(&pack_name)? else {
+        return Ok(None);
+    };
+
+    let kind = get_kind_dir_name(file_type_to_create).trim_end_matches('/');
+    if !manifest.kinds.iter().any(|pack_kind| pack_kind == kind) {
+        return Err(format!(
+            "The pack '{}' doesn't provide {} templates",
+            manifest.name, kind
+        ));
+    }
+
+    match find_template_in_dir(rest, &pack_dir.join(kind), extension, extension_order)? {
+        Some(tmpl_path) => Ok(Some(Template::Path(
+            tmpl_path,
+            TemplateLayer::Pack(manifest.name),
+        ))),
+        None => Err(format!(
+            "Couldn't find the template '{}' in the pack '{}' ({})",
+            rest.display(),
+            manifest.name,
+            manifest.version
+        )),
+    }
+}
+
+/// Searches a template inside a single directory
+fn find_template_in_dir(
+    template_arg_path: &Path,
+    dir: &Path,
+    extension: Option<&str>,
+    extension_order: &[String],
+) -> Result<Option<PathBuf>, String> {
+    let tmpl_dir = match template_arg_path.parent() {
+        Some(tmpl_parent) => dir.join(tmpl_parent),
+        None => dir.to_path_buf(),
+    };
+    if !tmpl_dir.is_dir() {
+        return Ok(None);
+    }
+
+    let tmpl_name = template_arg_path
+        .file_name()
+        .ok_or(String::from("Wrong template name"))?
+        .to_string_lossy();
+
+    let mut found_tmpls: Vec<PathBuf> = vec![];
+    for dir_entry in fs::read_dir(&tmpl_dir).map_err(|err| err.to_string())? {
+        let dir_entry_path = dir_entry.map_err(|err| err.to_string())?.path();
+        let is_match = dir_entry_path.is_file()
+            && dir_entry_path
+                .file_name()
+                .is_some_and(|file_name| is_template_file_of(&file_name.to_string_lossy(), &tmpl_name));
+        if is_match {
+            found_tmpls.push(dir_entry_path);
         }
     }
 
-    // If not, search the templates with the given name.
-    // It will return an error if none or more than one are found
-    if let Some(arg_tmpl_stem) = template_arg_path.file_stem() {
-        let found_tmpls = get_file_stem_occurrences(arg_tmpl_stem, &custom_templates_dir)?;
-
-        if found_tmpls.is_empty() {
-            Err(String::from("Couldn't find the provided template"))
-        } else if found_tmpls.len() > 1 {
-            return Err(String::from(
-                "Found multiple templates with the same name. Please specify the extension",
-            ));
-        } else {
-            return found_tmpls
-                .first()
-                .map(|p| Template::Path(p.to_owned()))
-                .ok_or(String::from("Couldn't find the provided template"));
+    choose_template_variant(&tmpl_name, found_tmpls, extension, extension_order)
+}
+
+/// Picks one of the templates sharing a name, like "card.tsx.hbs" and
+/// "card.jsx.hbs".
+///
+/// If the extension of the file to create is known, the template for it is
+/// used, or the one that doesn't define any extension ("card.hbs").
+/// Otherwise the first extension of `extension_order` (the
+/// `template_extension_order` configuration) that has a template is used, or
+/// the user is asked
+fn choose_template_variant(
+    tmpl_name: &str,
+    mut found_tmpls: Vec<PathBuf>,
+    extension: Option<&str>,
+    extension_order: &[String],
+) -> Result<Option<PathBuf>, String> {
+    if found_tmpls.len() <= 1 {
+        return Ok(found_tmpls.pop());
+    }
+    found_tmpls.sort();
+
+    let find_variant = |extension: Option<&str>| {
+        found_tmpls
+            .iter()
+            .find(|tmpl_path| get_template_target_extension(tmpl_path).as_deref() == extension)
+            .cloned()
+    };
+
+    if let Some(extension) = extension {
+        if let Some(tmpl_path) = find_variant(Some(extension)).or_else(|| find_variant(None)) {
+            return Ok(Some(tmpl_path));
         }
     } else {
-        Err(String::from("Wrong template name"))
+        if let Some(tmpl_path) = extension_order
+            .iter()
+            .find_map(|extension| find_variant(Some(extension)))
+        {
+            return Ok(Some(tmpl_path));
+        }
+
+        if cli_helper::is_interactive() {
+            let options: Vec<String> = found_tmpls
+                .iter()
+                .map(|tmpl_path| tmpl_path.display().to_string())
+                .collect();
+            let chosen = cli_helper::select_prompt(
+                &format!("Found multiple templates named '{}'. Which one should be used?", tmpl_name),
+                &options,
+            )?;
+            return Ok(Some(found_tmpls.swap_remove(chosen)));
+        }
+    }
+
+    Err(format!(
+        "Found multiple templates named '{}' ({}). Please specify the extension",
+        tmpl_name,
+        found_tmpls
+            .iter()
+            .map(|tmpl_path| tmpl_path.display().to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    ))
+}
+
+/// Extension of the file to create defined by a template's file name, like
+/// "tsx" for "card.tsx.hbs"
+fn get_template_target_extension(tmpl_path: &Path) -> Option<String> {
+    let file_name = tmpl_path.file_name()?.to_string_lossy();
+    let (tmpl_stem, _) = split_template_extension(&file_name)?;
+    Path::new(tmpl_stem)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+}
+
+/// Defines if the file is a template with the given name, like "card.hbs",
+/// "card.tsx.hbs" or "card.tsx.jinja" for the name "card"
+fn is_template_file_of(file_name: &str, template_name: &str) -> bool {
+    let Some((file_name, _)) = split_template_extension(file_name) else {
+        return false;
+    };
+
+    if file_name == template_name {
+        return true;
+    }
+
+    // A name without extension also matches the templates that define the
+    // extension of the file to create
+    match file_name
+        .strip_prefix(template_name)
+        .and_then(|rest| rest.strip_prefix('.'))
+    {
+        Some(extension) => {
+            !template_name.contains('.') && !extension.is_empty() && !extension.contains('.')
+        }
+        None => false,
+    }
+}
+
+/// Gets the built-in template of a page for the router it's created for, as
+/// only the app router passes `params` and `searchParams` to the pages
+pub fn get_default_page_template<'a>(extension: &str, router: &NextRouter) -> Template<'a> {
+    match (router, extension) {
+        (NextRouter::PageRouter, "tsx") => Template::Str(DEFAULT_TSX_PAGE_ROUTER_TEMPLATE),
+        (NextRouter::PageRouter, "ts") => Template::Str(DEFAULT_TS_PAGE_ROUTER_TEMPLATE),
+        _ => get_default_template(&CreateableFileType::Page, extension),
     }
 }
 
-pub fn get_default_template<'a>(file: &CreateableFileType) -> Template<'a> {
-    let template_content = match file {
-        CreateableFileType::Page => DEFAULT_PAGE_TEMPLATE,
-        CreateableFileType::ApiPage => DEFAULT_API_PAGE_TEMPLATE,
-        CreateableFileType::Stylesheet => DEFAULT_STYLESHEET_TEMPLATE,
-        CreateableFileType::Component => DEFAULT_COMPONENT_TEMPLATE,
+/// Whether the built-in template is one of the page router's pages
+pub fn is_page_router_template(template_content: &str) -> bool {
+    [DEFAULT_TSX_PAGE_ROUTER_TEMPLATE, DEFAULT_TS_PAGE_ROUTER_TEMPLATE].contains(&template_content)
+}
+
+/// Gets the built-in template for the kind of file and the extension it will
+/// have, so typescript files get typed templates. Pages get the app router's
+/// ones (see `get_default_page_template`)
+pub fn get_default_template<'a>(file: &CreateableFileType, extension: &str) -> Template<'a> {
+    let template_content = match (file, extension) {
+        (CreateableFileType::Page, "tsx") => DEFAULT_TSX_PAGE_TEMPLATE,
+        (CreateableFileType::Page, "ts") => DEFAULT_TS_PAGE_TEMPLATE,
+        (CreateableFileType::Page, _) => DEFAULT_PAGE_TEMPLATE,
+        (CreateableFileType::ApiPage, "ts" | "tsx") => DEFAULT_TS_API_PAGE_TEMPLATE,
+        (CreateableFileType::ApiPage, _) => DEFAULT_API_PAGE_TEMPLATE,
+        (CreateableFileType::Stylesheet, _) => DEFAULT_STYLESHEET_TEMPLATE,
+        (CreateableFileType::Component, "tsx") => DEFAULT_TSX_COMPONENT_TEMPLATE,
+        (CreateableFileType::Component, "ts") => DEFAULT_TS_COMPONENT_TEMPLATE,
+        (CreateableFileType::Component, _) => DEFAULT_COMPONENT_TEMPLATE,
     };
 
     Template::Str(template_content)
 }
 
-fn get_custom_templates_dir(file: &CreateableFileType) -> Result<PathBuf, String> {
-    let mut custom_templates_path = PathBuf::from(format!("{}/{}/", NEXT_BUTLER_DIR, "templates/"));
-    match file {
-        CreateableFileType::Page => custom_templates_path.push("pages/"),
-        CreateableFileType::ApiPage => custom_templates_path.push("api-pages/"),
-        CreateableFileType::Stylesheet => custom_templates_path.push("stylesheets/"),
-        CreateableFileType::Component => custom_templates_path.push("components/"),
+/// A file inside the partials/ folder of a template layer
+pub struct Partial {
+    /// Name used to include it, like "license/mit"
+    pub name: String,
+    pub path: PathBuf,
+    pub engine: TemplateEngine,
+}
+
+/// Gets every file inside the partials/ folder of each template layer, named
+/// after its path relative to that folder, without the template's extension.
+/// They are sorted from the lowest to the highest precedence, so partials of
+/// a layer override the ones with the same name from the layers before.
+///
+/// For example, `partials/license/mit.hbs` can be used with `{{> license/mit}}`
+/// and `partials/license/mit.jinja` with `{% include "license/mit" %}`
+///
+/// Partials of installed packs are prefixed with the pack's name, like
+/// `{{> acme/license/mit}}`. When rendering a pack's template, the partials of
+/// that pack are also available without the prefix, overriding the others
+fn get_partials(template_layer: &TemplateLayer) -> Result<Vec<Partial>, String> {
+    let mut partials_dirs: Vec<(String, PathBuf)> = vec![];
+    let mut own_pack_partials_dir = None;
+    for (manifest, pack_dir) in get_installed_packs()? {
+        let pack_partials_dir = pack_dir.join(PARTIALS_DIR_NAME);
+        if *template_layer == TemplateLayer::Pack(manifest.name.to_owned()) {
+            own_pack_partials_dir = Some((String::new(), pack_partials_dir.to_owned()));
+        }
+        partials_dirs.push((format!("{}/", manifest.name), pack_partials_dir));
+    }
+    for (_, layer_dir) in get_template_layers()?.into_iter().rev() {
+        partials_dirs.push((String::new(), layer_dir.join(PARTIALS_DIR_NAME)));
     }
+    partials_dirs.extend(own_pack_partials_dir);
 
-    if custom_templates_path.exists() {
-        Ok(custom_templates_path)
-    } else {
-        Err(String::from("Custom templates directory is not defined"))
+    let mut partials = vec![];
+    for (name_prefix, partials_dir) in partials_dirs {
+        for partial_path in file_helper::get_files_recursively(&partials_dir)? {
+            let relative_path = partial_path
+                .strip_prefix(&partials_dir)
+                .map_err(|err| err.to_string())?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let (partial_name, engine) = split_template_extension(&relative_path)
+                .unwrap_or((&relative_path, TemplateEngine::Handlebars));
+
+            partials.push(Partial {
+                name: format!("{}{}", name_prefix, partial_name),
+                path: partial_path.to_owned(),
+                engine,
+            });
+        }
     }
+
+    Ok(partials)
 }
 
-pub fn create_pages_templates<P>(pages_templates_dir: P) -> Result<(), String>
-where
-    P: AsRef<Path>,
-{
-    fs::create_dir_all(pages_templates_dir.as_ref())
-        .map_err(|err| format!("Error creating page templates folder: {}", err))
-        .and_then(|()| {
-            file_helper::create(
-                &PathBuf::from(pages_templates_dir.as_ref()).join("default.jsx.hbs"),
-                DEFAULT_PAGE_TEMPLATE.as_bytes().to_vec(),
-            )
-        })
-        .and_then(|()| {
-            file_helper::create(
-                &PathBuf::from(pages_templates_dir.as_ref()).join("api/default.js.hbs"),
-                DEFAULT_API_PAGE_TEMPLATE.as_bytes().to_vec(),
-            )
+/// Reads the partials rendered by the given engine, adding their content to
+/// `sources`, to locate the errors. Returns their names
+fn load_partials(
+    partials: Vec<Partial>,
+    engine: TemplateEngine,
+    sources: &mut TemplateErrorSources,
+) -> Result<Vec<String>, String> {
+    let mut partial_names = vec![];
+    for partial in partials.into_iter().filter(|partial| partial.engine == engine) {
+        let content = fs::read_to_string(&partial.path).map_err(|err| {
+            format!("Error reading partial {}: {}", partial.path.display(), err)
         })?;
+        sources.insert(
+            partial.name.to_owned(),
+            TemplateErrorSource {
+                display_name: partial.path.display().to_string(),
+                content,
+                line_offset: 0,
+            },
+        );
+        partial_names.push(partial.name);
+    }
 
-    Ok(())
+    Ok(partial_names)
 }
 
-pub fn create_components_templates<P>(components_tempaltes_dir: P) -> Result<(), String>
+/// Creates every built-in template as a custom one inside the given templates
+/// directory, so they can be used as examples
+pub fn create_default_templates<P>(templates_dir: P) -> Result<(), String>
 where
     P: AsRef<Path>,
 {
-    fs::create_dir_all(components_tempaltes_dir.as_ref())
-        .map_err(|err| format!("Error creating component templates folder: {}", err))
-        .and_then(|()| {
-            file_helper::create(
-                &PathBuf::from(components_tempaltes_dir.as_ref()).join("default.jsx.hbs"),
-                DEFAULT_COMPONENT_TEMPLATE.as_bytes().to_vec(),
-            )
-        })?;
+    for (tmpl_path, tmpl_content) in DEFAULT_TEMPLATE_FILES {
+        file_helper::create(
+            &templates_dir.as_ref().join(tmpl_path),
+            tmpl_content.as_bytes().to_vec(),
+        )
+        .map_err(|err| format!("Error creating template {}: {}", tmpl_path, err))?;
+    }
 
     Ok(())
 }
 
-pub fn create_stylesheets_templates<P>(stylesheets_templates_dir: P) -> Result<(), String>
-where
-    P: AsRef<Path>,
-{
-    fs::create_dir_all(stylesheets_templates_dir.as_ref())
-        .map_err(|err| format!("Error creating stylesheet templates folder: {}", err))
-        .and_then(|()| {
-            file_helper::create(
-                &PathBuf::from(stylesheets_templates_dir.as_ref()).join("default.css.hbs"),
-                DEFAULT_STYLESHEET_TEMPLATE.as_bytes().to_vec(),
-            )
-        })?;
+/// Builds the handlebars registry used to render the templates, with all the
+/// next-butler helpers registered. Values are written as they are, since the
+/// output is source code and not HTML
+pub fn new_registry<'reg>() -> Handlebars<'reg> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    helpers::register_helpers(&mut handlebars);
+    handlebars
+}
 
-    Ok(())
+/// Renders the output path pattern of a new file (like
+/// "{{folder}}/{{kebab name}}/index.{{ext}}").
+///
+/// Besides the template variables, the pattern can use:
+/// - `folder`: the folder where the file would be created by default
+/// - `path`: the path given as argument, without its extension
+/// - `ext`: the extension of the new file
+/// - `src_dir`, `app_dir`, `pages_dir`, `public_dir` and `components_dir`:
+///   the folders of the project
+/// - `{{segment N}}`: the Nth folder or file of the path given as argument,
+///   starting at 0
+///
+/// The resulting path must be relative to the project's root, stay inside of
+/// it and not collide with an existing file.
+pub fn render_path_pattern(
+    pattern: &str,
+    template_vars: &TemplateVars,
+    project_dirs: &ProjectDirs,
+    folder: &Path,
+    path_arg: &Path,
+    extension: &str,
+    strict: bool,
+) -> Result<PathBuf, String> {
+    let rendered_path = render_output_path(
+        pattern,
+        template_vars,
+        project_dirs,
+        folder,
+        path_arg,
+        extension,
+        strict,
+    )?;
+    check_path_collision(&rendered_path)?;
+
+    Ok(rendered_path)
 }
 
-pub fn create_from_template(new_file_path: &Path, template: Template, template_vars: &BTreeMap<String, String>) -> Result<(), String> {
-    let mut handlebars = Handlebars::new();
-    match template {
-        Template::Str(tmpl_content) => {
-            handlebars
-                .register_template_string("template", tmpl_content)
-                .map_err(|err| err.to_string())?;
-        }
-        Template::Path(tmpl_path) => {
-            handlebars
-                .register_template_file("template", tmpl_path)
-                .map_err(|err| err.to_string())?;
+/// Folder where [render_path_pattern] would create the new file. It's used
+/// before the template is loaded, so the variables it defines are rendered
+/// empty and the new file's path isn't checked
+pub fn render_path_pattern_dir(
+    pattern: &str,
+    template_vars: &TemplateVars,
+    project_dirs: &ProjectDirs,
+    folder: &Path,
+    path_arg: &Path,
+    extension: &str,
+) -> Result<PathBuf, String> {
+    let rendered_path = render_output_path(
+        pattern,
+        template_vars,
+        project_dirs,
+        folder,
+        path_arg,
+        extension,
+        false,
+    )?;
+
+    Ok(rendered_path.parent().map(Path::to_path_buf).unwrap_or_default())
+}
+
+/// Renders the output path pattern, checking it stays inside the project
+fn render_output_path(
+    pattern: &str,
+    template_vars: &TemplateVars,
+    project_dirs: &ProjectDirs,
+    folder: &Path,
+    path_arg: &Path,
+    extension: &str,
+    strict: bool,
+) -> Result<PathBuf, String> {
+    let mut pattern_vars = template_vars.clone();
+    let path_arg = path_arg.strip_prefix("/").unwrap_or(path_arg).with_extension("");
+    for (var_name, value) in project_dirs.as_vars().into_iter().chain([
+        ("folder", folder.to_string_lossy().to_string()),
+        ("path", path_arg.to_string_lossy().replace('\\', "/")),
+        ("ext", extension.to_owned()),
+    ]) {
+        pattern_vars.insert(var_name.to_owned(), value);
+    }
+
+    let path_segments: Vec<String> = path_arg
+        .iter()
+        .map(|segment| segment.to_string_lossy().to_string())
+        .collect();
+    let mut handlebars = new_registry();
+    handlebars.set_strict_mode(strict);
+    handlebars.register_helper(
+        "segment",
+        Box::new(
+            move |h: &Helper,
+                  _: &Handlebars,
+                  _: &Context,
+                  _: &mut RenderContext,
+                  out: &mut dyn Output|
+                  -> HelperResult {
+                let index = h
+                    .param(0)
+                    .and_then(|param| param.value().as_u64())
+                    .ok_or(RenderErrorReason::ParamNotFoundForIndex("segment", 0))?;
+                let segment = path_segments.get(index as usize).ok_or_else(|| {
+                    RenderErrorReason::Other(format!(
+                        "The path has no segment {} (it has {})",
+                        index,
+                        path_segments.len()
+                    ))
+                })?;
+                out.write(segment)?;
+                Ok(())
+            },
+        ),
+    );
+
+    let rendered_path = handlebars
+        .render_template(pattern, &pattern_vars)
+        .map_err(|err| format!("Invalid output path pattern '{}': {}", pattern, err))?;
+    let rendered_path = PathBuf::from(rendered_path.trim()).clean();
+
+    if rendered_path.is_absolute() || rendered_path.starts_with("..") {
+        return Err(format!(
+            "The output path {} must be inside the project",
+            rendered_path.display()
+        ));
+    }
+
+    Ok(rendered_path)
+}
+
+/// Fails if the new file would replace an existing one, or if it is a script
+/// and there is another script with the same name next to it (like
+/// "page.jsx" and "page.tsx"). Other files with the same name, like a
+/// component's stylesheet, are fine
+fn check_path_collision(new_file_path: &Path) -> Result<(), String> {
+    if new_file_path.exists() {
+        return Err(format!("{} already exists", new_file_path.display()));
+    }
+    if !is_script(new_file_path) {
+        return Ok(());
+    }
+
+    let (Some(parent_dir), Some(file_stem)) = (new_file_path.parent(), new_file_path.file_stem())
+    else {
+        return Ok(());
+    };
+    let parent_dir = if parent_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        parent_dir
+    };
+    if !parent_dir.is_dir() {
+        return Ok(());
+    }
+
+    match file_helper::get_file_stem_occurrences(file_stem, parent_dir)?
+        .into_iter()
+        .find(|existing_file| is_script(existing_file))
+    {
+        Some(existing_file) => Err(format!(
+            "{} collides with the existing file {}",
+            new_file_path.display(),
+            existing_file.display()
+        )),
+        None => Ok(()),
+    }
+}
+
+fn is_script(file_path: &Path) -> bool {
+    file_path
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|extension| SCRIPT_EXTENSIONS.contains(&extension))
+}
+
+/// Renders the template, returning its content. The engine is chosen by the
+/// template's extension.
+///
+/// When `strict` is true, using a variable that is not defined fails instead
+/// of rendering an empty string
+pub fn render_template(
+    template: &Template,
+    template_vars: &TemplateVars,
+    strict: bool,
+) -> Result<String, TemplateError> {
+    let engine = template.get_engine();
+    let mut sources = TemplateErrorSources::new();
+    let partial_names = load_partials(get_partials(&template.get_layer())?, engine, &mut sources)?;
+
+    let template_name = template.get_display_name();
+    let template_source = template.load()?;
+    sources.insert(
+        template_name.to_owned(),
+        TemplateErrorSource {
+            display_name: template_name.to_owned(),
+            content: template_source.body,
+            line_offset: template_source.body_line_offset,
+        },
+    );
+
+    match engine {
+        TemplateEngine::Handlebars => {
+            render_handlebars(&template_name, &partial_names, &sources, template_vars, strict)
         }
+        TemplateEngine::Jinja => jinja_engine::render_jinja(
+            &template_name,
+            &partial_names,
+            &sources,
+            template_vars,
+            strict,
+        ),
+    }
+}
+
+fn render_handlebars(
+    template_name: &str,
+    partial_names: &[String],
+    sources: &TemplateErrorSources,
+    template_vars: &TemplateVars,
+    strict: bool,
+) -> Result<String, TemplateError> {
+    let mut handlebars = new_registry();
+    handlebars.set_strict_mode(strict);
+
+    for partial_name in partial_names {
+        let source = &sources[partial_name];
+        handlebars
+            .register_partial(partial_name, &source.content)
+            .map_err(|err| TemplateError::from_compile_error(&err, source))?;
     }
 
+    let source = &sources[template_name];
+    handlebars
+        .register_template_string(template_name, &source.content)
+        .map_err(|err| TemplateError::from_compile_error(&err, source))?;
+
+    handlebars
+        .render(template_name, template_vars)
+        .map_err(|err| TemplateError::from_render_error(&err, template_name, sources, template_vars))
+}
+
+/// Renders the template into a new file, recording how it was generated in
+/// nextbutler/manifest.json if the project has a nextbutler/ folder.
+///
+/// When `strict` is true, using a variable that is not defined fails instead
+/// of rendering an empty string
+pub fn create_from_template(
+    new_file_path: &Path,
+    file_type: &CreateableFileType,
+    template: Template,
+    template_vars: &BTreeMap<String, String>,
+    strict: bool,
+) -> Result<(), String> {
+    // Render before creating the file, so a failed render doesn't leave an
+    // empty file behind
+    let content = render_template(&template, template_vars, strict)?;
+    let content = format_output(&content, new_file_path, template.is_built_in())?;
+
     if let Some(parent_dir) = new_file_path.parent() {
         fs::create_dir_all(parent_dir).map_err(|err| format!("Error creating file: {}", err))?;
     }
-    let file = File::create_new(new_file_path).map_err(|err| format!("Error creating file: {}", err))?;
+    let mut file = File::create_new(new_file_path).map_err(|err| format!("Error creating file: {}", err))?;
 
-    handlebars
-        .render_to_write("template", &template_vars, &file)
-        .map_err(|err| err.to_string())
+    file.write_all(content.as_bytes())
+        .map_err(|err| format!("Error creating file: {}", err))?;
+
+    // Only projects set up with `nb init` keep track of the generated files
+    if !Path::new(NEXT_BUTLER_DIR).is_dir() {
+        return Ok(());
+    }
+    let mut provenance = Provenance::read()?;
+    provenance.record(
+        new_file_path,
+        GeneratedFile::new(file_type, &template, template_vars, strict, &content)?,
+        &content,
+    )?;
+    provenance.save()
 }
@@ -0,0 +1,381 @@
+//! Formats the rendered files following the project's .editorconfig and
+//! Prettier configuration, so they pass its lint without a separate format
+//! step.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::Path,
+};
+
+use regex::Regex;
+use serde::Deserialize;
+
+const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// Files where Prettier's configuration is searched, in order. They can be
+/// written in JSON or YAML
+const PRETTIER_CONFIG_FILE_NAMES: [&str; 4] = [
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.yaml",
+    ".prettierrc.yml",
+];
+
+/// Extensions of the files whose quotes and semicolons can be changed
+const SCRIPT_EXTENSIONS: [&str; 4] = ["js", "jsx", "ts", "tsx"];
+
+/// Width of an indentation level when the content has no indented lines
+const DEFAULT_INDENT_WIDTH: usize = 2;
+
+/// Options of Prettier's configuration that are applied
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PrettierConfig {
+    tab_width: Option<usize>,
+    use_tabs: Option<bool>,
+    semi: Option<bool>,
+    single_quote: Option<bool>,
+}
+
+/// How a generated file must be formatted. Unset options leave the rendered
+/// content as it is
+#[derive(Debug, Default, PartialEq)]
+pub struct OutputFormat {
+    pub use_tabs: Option<bool>,
+    pub indent_width: Option<usize>,
+    /// "\n", "\r\n" or "\r"
+    pub line_ending: Option<&'static str>,
+    pub final_newline: Option<bool>,
+    pub trim_trailing_whitespace: bool,
+    /// Only applied to built-in templates
+    pub semi: Option<bool>,
+    /// Only applied to built-in templates
+    pub single_quote: Option<bool>,
+}
+
+impl OutputFormat {
+    /// Reads the format of the given file from the .editorconfig files above
+    /// it and the closest Prettier configuration, which takes precedence
+    pub fn for_file(file_path: &Path) -> Result<Self, String> {
+        let mut output_format = Self::default();
+        let file_path = env::current_dir()
+            .map_err(|err| err.to_string())?
+            .join(file_path);
+
+        for (key, value) in get_editorconfig_properties(&file_path)? {
+            match (key.as_str(), value.as_str()) {
+                ("indent_style", "tab") => output_format.use_tabs = Some(true),
+                ("indent_style", "space") => output_format.use_tabs = Some(false),
+                ("indent_size" | "tab_width", size) => {
+                    if let Ok(size) = size.parse() {
+                        output_format.indent_width = Some(size);
+                    }
+                }
+                ("end_of_line", "lf") => output_format.line_ending = Some("\n"),
+                ("end_of_line", "crlf") => output_format.line_ending = Some("\r\n"),
+                ("end_of_line", "cr") => output_format.line_ending = Some("\r"),
+                ("insert_final_newline", value) => {
+                    output_format.final_newline = value.parse().ok();
+                }
+                ("trim_trailing_whitespace", value) => {
+                    output_format.trim_trailing_whitespace = value == "true";
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(prettier_config) = get_prettier_config(&file_path)? {
+            output_format.use_tabs = prettier_config.use_tabs.or(output_format.use_tabs);
+            output_format.indent_width = prettier_config.tab_width.or(output_format.indent_width);
+            output_format.semi = prettier_config.semi;
+            output_format.single_quote = prettier_config.single_quote;
+        }
+
+        Ok(output_format)
+    }
+
+    /// Formats the rendered content of a file with the given extension.
+    /// Quotes and semicolons are only changed in built-in templates, whose
+    /// code is known
+    pub fn format(&self, content: &str, extension: &str, built_in: bool) -> String {
+        let is_script = SCRIPT_EXTENSIONS.contains(&extension);
+        // Not every built-in template uses the same indentation either
+        let source_indent_width = detect_indent_width(content).unwrap_or(DEFAULT_INDENT_WIDTH);
+
+        let mut lines: Vec<String> = content
+            .lines()
+            .map(|line| {
+                let mut line = self.reindent(line, source_indent_width);
+                if self.trim_trailing_whitespace {
+                    line.truncate(line.trim_end().len());
+                }
+                if built_in && is_script {
+                    if self.semi == Some(false) {
+                        remove_semicolon(&mut line);
+                    }
+                    if self.single_quote == Some(true) {
+                        line = use_single_quotes(&line);
+                    }
+                }
+                line
+            })
+            .collect();
+
+        let ends_with_newline = match self.final_newline {
+            Some(final_newline) => {
+                while lines.last().is_some_and(|line| line.is_empty()) {
+                    lines.pop();
+                }
+                final_newline
+            }
+            None => content.ends_with('\n'),
+        };
+        let line_ending = self.line_ending.unwrap_or(if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        });
+
+        let mut formatted = lines.join(line_ending);
+        if ends_with_newline && !lines.is_empty() {
+            formatted.push_str(line_ending);
+        }
+        formatted
+    }
+
+    /// Changes the indentation of the line, made of levels of
+    /// `source_indent_width` spaces or tabs, to the configured one
+    fn reindent(&self, line: &str, source_indent_width: usize) -> String {
+        if self.use_tabs.is_none() && self.indent_width.is_none() {
+            return line.to_owned();
+        }
+        let content = line.trim_start_matches([' ', '\t']);
+        let indent = &line[..line.len() - content.len()];
+
+        let tabs = indent.chars().filter(|ch| *ch == '\t').count();
+        let spaces = indent.len() - tabs;
+        let levels = tabs + spaces / source_indent_width;
+        let alignment = spaces % source_indent_width;
+
+        let indent_unit = match (self.use_tabs, self.indent_width) {
+            (Some(true), _) => String::from("\t"),
+            (_, Some(indent_width)) => " ".repeat(indent_width),
+            (_, None) => " ".repeat(source_indent_width),
+        };
+        format!(
+            "{}{}{}",
+            indent_unit.repeat(levels),
+            " ".repeat(alignment),
+            content
+        )
+    }
+}
+
+/// Formats the rendered content of the given file
+pub fn format_output(content: &str, file_path: &Path, built_in: bool) -> Result<String, String> {
+    let extension = file_path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    Ok(OutputFormat::for_file(file_path)?.format(content, &extension, built_in))
+}
+
+/// Width of an indentation level of the content, which is the smallest
+/// indentation made of spaces. Continuation lines of block comments are
+/// ignored, as they are aligned with a single space
+fn detect_indent_width(content: &str) -> Option<usize> {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('*'))
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .filter(|spaces| *spaces > 0)
+        .min()
+}
+
+fn remove_semicolon(line: &mut String) {
+    let trimmed_len = line.trim_end().len();
+    if line[..trimmed_len].ends_with(';') {
+        line.replace_range(trimmed_len - 1..trimmed_len, "");
+    }
+}
+
+/// Replaces the double quotes of the strings of a line of code with single
+/// ones, unless the string contains a single quote
+fn use_single_quotes(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('"') {
+        result.push_str(&rest[..start]);
+        let string = &rest[start + 1..];
+        let Some(end) = find_string_end(string) else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let inner = &string[..end];
+        if inner.contains('\'') {
+            result.push_str(&rest[start..start + end + 2]);
+        } else {
+            result.push('\'');
+            result.push_str(&inner.replace("\\\"", "\""));
+            result.push('\'');
+        }
+        rest = &string[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Position of the double quote closing a string, skipping the escaped ones
+fn find_string_end(string: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (position, ch) in string.char_indices() {
+        match ch {
+            '\\' => escaped = !escaped,
+            '"' if !escaped => return Some(position),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// Properties of every .editorconfig section matching the file, from the
+/// closest file to the farthest until one with `root = true`. Closer files
+/// and later sections take precedence
+fn get_editorconfig_properties(file_path: &Path) -> Result<BTreeMap<String, String>, String> {
+    let mut editorconfigs = vec![];
+    for dir in file_path.ancestors().skip(1) {
+        let editorconfig_path = dir.join(EDITORCONFIG_FILE_NAME);
+        if !editorconfig_path.is_file() {
+            continue;
+        }
+
+        let content = fs::read_to_string(&editorconfig_path)
+            .map_err(|err| format!("Error reading {}: {}", editorconfig_path.display(), err))?;
+        let is_root = parse_editorconfig(&content)
+            .into_iter()
+            .find(|(section, _)| section.is_none())
+            .is_some_and(|(_, properties)| {
+                properties.get("root").is_some_and(|root| root == "true")
+            });
+        editorconfigs.push((dir.to_owned(), content));
+        if is_root {
+            break;
+        }
+    }
+
+    let mut properties = BTreeMap::new();
+    for (dir, content) in editorconfigs.into_iter().rev() {
+        let Ok(relative_path) = file_path.strip_prefix(&dir) else {
+            continue;
+        };
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+
+        for (section, section_properties) in parse_editorconfig(&content) {
+            if section.is_some_and(|glob| editorconfig_glob_matches(&glob, &relative_path)) {
+                properties.extend(section_properties);
+            }
+        }
+    }
+
+    Ok(properties)
+}
+
+/// Sections of an .editorconfig file, with their lowercase properties. The
+/// properties before the first section have no glob
+fn parse_editorconfig(content: &str) -> Vec<(Option<String>, BTreeMap<String, String>)> {
+    let mut sections = vec![(None, BTreeMap::new())];
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+
+        if let Some(glob) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            sections.push((Some(glob.to_owned()), BTreeMap::new()));
+        } else if let Some((key, value)) = line.split_once('=') {
+            if let Some((_, properties)) = sections.last_mut() {
+                properties.insert(key.trim().to_lowercase(), value.trim().to_lowercase());
+            }
+        }
+    }
+
+    sections
+}
+
+/// Whether the path, relative to the .editorconfig's directory, matches the
+/// section's glob. Globs without a "/" match files in any directory
+fn editorconfig_glob_matches(glob: &str, relative_path: &str) -> bool {
+    let glob = match glob.strip_prefix('/') {
+        Some(glob) => glob.to_owned(),
+        None if glob.contains('/') => glob.to_owned(),
+        None => format!("**/{}", glob),
+    };
+
+    let mut pattern = String::from("^");
+    let mut in_class = false;
+    let mut chars = glob.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ']' => {
+                in_class = false;
+                pattern.push(ch);
+            }
+            '!' if pattern.ends_with('[') => pattern.push('^'),
+            ch if in_class => pattern.push(ch),
+            '[' => {
+                in_class = true;
+                pattern.push(ch);
+            }
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '{' => pattern.push_str("(?:"),
+            '}' => pattern.push(')'),
+            ',' => pattern.push('|'),
+            ch => pattern.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern).is_ok_and(|regex| regex.is_match(relative_path))
+}
+
+/// Reads the closest Prettier configuration above the file, if any
+fn get_prettier_config(file_path: &Path) -> Result<Option<PrettierConfig>, String> {
+    let Some(config_path) = file_path.ancestors().skip(1).find_map(|dir| {
+        PRETTIER_CONFIG_FILE_NAMES
+            .iter()
+            .map(|file_name| dir.join(file_name))
+            .find(|config_path| config_path.is_file())
+    }) else {
+        return Ok(None);
+    };
+
+    parse_prettier_config(&config_path).map(Some)
+}
+
+/// Parses a Prettier configuration file. YAML is a superset of JSON, so both
+/// formats are parsed as YAML. A configuration referring to a shared one,
+/// like `"@company/prettier-config"`, is not followed
+fn parse_prettier_config(config_path: &Path) -> Result<PrettierConfig, String> {
+    let content = fs::read_to_string(config_path)
+        .map_err(|err| format!("Error reading {}: {}", config_path.display(), err))?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&content)
+        .map_err(|err| format!("Invalid {}: {}", config_path.display(), err))?;
+    if !config.is_mapping() {
+        return Ok(PrettierConfig::default());
+    }
+
+    serde_yaml::from_value(config)
+        .map_err(|err| format!("Invalid {}: {}", config_path.display(), err))
+}
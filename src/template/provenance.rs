@@ -0,0 +1,402 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use diffy::PatchFormatter;
+use path_clean::PathClean;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{constants::NEXT_BUTLER_DIR, helpers::file_helper, CreateableFileType, NextRouter};
+
+use super::{
+    get_default_page_template, get_default_template, is_page_router_template,
+    output_format::format_output, render_template, template_layer::TemplateLayer,
+    template_variables::TemplateVars, Template,
+};
+
+/// Name of the file, inside the tool's directory, recording how every file
+/// was generated
+pub const PROVENANCE_FILE_NAME: &str = "manifest.json";
+
+/// Folder, inside the tool's directory, holding a copy of the original
+/// render of every generated file, named after its hash. They are the base of
+/// the three-way merges done by `nb template update`
+const RENDERS_DIR_NAME: &str = "renders/";
+
+/// Name recorded instead of a path for the built-in templates
+const BUILT_IN_TEMPLATE_NAME: &str = "built-in";
+
+/// Name recorded for the built-in templates of the page router's pages
+const BUILT_IN_PAGE_ROUTER_TEMPLATE_NAME: &str = "built-in (page router)";
+
+/// Every file generated from a template, by its path relative to the
+/// project's root
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct Provenance {
+    pub files: BTreeMap<String, GeneratedFile>,
+}
+
+/// How a file was generated
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GeneratedFile {
+    pub kind: CreateableFileType,
+    /// Path of the template, or "built-in" (with the router, for pages of the
+    /// page router)
+    pub template: String,
+    pub layer: TemplateLayer,
+    /// Hash of the template's content when the file was last rendered. Empty
+    /// in manifests written before it was recorded
+    #[serde(default)]
+    pub template_hash: String,
+    /// Hash of the last render, naming its copy inside nextbutler/renders/.
+    /// A different render means something the file depends on changed: the
+    /// template, its partials or the built-in helpers
+    pub output_hash: String,
+    pub variables: TemplateVars,
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl Provenance {
+    /// Reads the manifest of generated files, which is empty if it doesn't
+    /// exist yet
+    pub fn read() -> Result<Self, String> {
+        let provenance_path = get_provenance_path();
+        if !provenance_path.is_file() {
+            return Ok(Self::default());
+        }
+
+        file_helper::json_file_to_struct(&provenance_path)
+            .map_err(|err| format!("Invalid {}: {}", provenance_path.display(), err))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let provenance_path = get_provenance_path();
+        let content = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+        fs::create_dir_all(NEXT_BUTLER_DIR).map_err(|err| err.to_string())?;
+        fs::write(&provenance_path, content + "\n")
+            .map_err(|err| format!("Error writing {}: {}", provenance_path.display(), err))
+    }
+
+    /// Records how the file was generated, keeping a copy of the rendered
+    /// content
+    pub fn record(
+        &mut self,
+        file_path: &Path,
+        generated_file: GeneratedFile,
+        rendered: &str,
+    ) -> Result<(), String> {
+        let renders_dir = get_renders_dir();
+        fs::create_dir_all(&renders_dir)
+            .map_err(|err| format!("Error creating {}: {}", renders_dir.display(), err))?;
+        fs::write(renders_dir.join(&generated_file.output_hash), rendered)
+            .map_err(|err| format!("Error saving the render of {}: {}", file_path.display(), err))?;
+
+        let previous = self
+            .files
+            .insert(get_file_key(file_path), generated_file);
+        if let Some(previous) = previous {
+            self.remove_unused_render(&previous.output_hash);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the copy of a render when no file uses it anymore
+    fn remove_unused_render(&self, output_hash: &str) {
+        if !self
+            .files
+            .values()
+            .any(|generated_file| generated_file.output_hash == output_hash)
+        {
+            let _ = fs::remove_file(get_renders_dir().join(output_hash));
+        }
+    }
+}
+
+impl GeneratedFile {
+    pub fn new(
+        kind: &CreateableFileType,
+        template: &Template,
+        template_vars: &TemplateVars,
+        strict: bool,
+        rendered: &str,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            kind: kind.to_owned(),
+            template: match template {
+                Template::Str(content) if is_page_router_template(content) => {
+                    String::from(BUILT_IN_PAGE_ROUTER_TEMPLATE_NAME)
+                }
+                Template::Str(_) => String::from(BUILT_IN_TEMPLATE_NAME),
+                Template::Path(tmpl_path, _) => tmpl_path.to_string_lossy().replace('\\', "/"),
+            },
+            layer: template.get_layer(),
+            template_hash: hash_content(&template.read()?),
+            output_hash: hash_content(rendered),
+            variables: template_vars.to_owned(),
+            strict,
+        })
+    }
+
+    /// Gets the template the file was generated from. Built-in templates are
+    /// picked by the file's extension
+    pub fn get_template<'a>(&self, file_path: &Path) -> Template<'a> {
+        let extension = file_path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match self.template.as_str() {
+            BUILT_IN_TEMPLATE_NAME => return get_default_template(&self.kind, &extension),
+            BUILT_IN_PAGE_ROUTER_TEMPLATE_NAME => {
+                return get_default_page_template(&extension, &NextRouter::PageRouter)
+            }
+            _ => {}
+        }
+
+        Template::Path(PathBuf::from(&self.template), self.layer.to_owned())
+    }
+
+    /// Renders the file again with the current content of its template,
+    /// formatted as new files are
+    pub fn render(&self, file_path: &Path) -> Result<String, String> {
+        let template = self.get_template(file_path);
+        let rendered = render_template(&template, &self.variables, self.strict)?;
+        format_output(&rendered, file_path, template.is_built_in())
+    }
+
+    /// Hashes the current content of the file's template
+    pub fn hash_template(&self, file_path: &Path) -> Result<String, String> {
+        Ok(hash_content(&self.get_template(file_path).read()?))
+    }
+
+    /// Whether the template's content changed since the file was last
+    /// rendered. Unknown for files recorded without the template's hash
+    pub fn template_changed(&self, template_hash: &str) -> bool {
+        !self.template_hash.is_empty() && self.template_hash != template_hash
+    }
+
+    /// Reads the copy of the file's last render, if it still exists
+    pub fn read_original_render(&self) -> Option<String> {
+        fs::read_to_string(get_renders_dir().join(&self.output_hash)).ok()
+    }
+}
+
+/// Result of updating a generated file
+pub enum FileUpdate {
+    /// Its template didn't change
+    UpToDate,
+    Updated,
+    /// Its template changed without changing its render, so only the
+    /// manifest was updated
+    TemplateChanged,
+    /// Updated, but the changes made to the file and to the template touch
+    /// the same lines, which now hold conflict markers
+    Conflicted,
+    /// It couldn't be updated, for the given reason
+    Skipped(String),
+}
+
+/// Re-renders every generated file (of the given kind) whose template or
+/// render changed since it was generated, and merges the changes into it. The changes made to
+/// the file since it was generated are kept, using the original render as
+/// the base of the three-way merge
+pub fn update_generated_files(
+    kind: Option<CreateableFileType>,
+) -> Result<Vec<(String, FileUpdate)>, String> {
+    let mut provenance = Provenance::read()?;
+    let mut updates = vec![];
+
+    let generated_files: Vec<(String, GeneratedFile)> = provenance
+        .files
+        .iter()
+        .filter(|(_, generated_file)| kind.is_none() || kind == Some(generated_file.kind))
+        .map(|(file_key, generated_file)| (file_key.to_owned(), generated_file.to_owned()))
+        .collect();
+    for (file_key, generated_file) in generated_files {
+        let update = update_generated_file(&mut provenance, Path::new(&file_key), &generated_file)
+            .unwrap_or_else(FileUpdate::Skipped);
+        updates.push((file_key, update));
+    }
+
+    provenance.save()?;
+    Ok(updates)
+}
+
+fn update_generated_file(
+    provenance: &mut Provenance,
+    file_path: &Path,
+    generated_file: &GeneratedFile,
+) -> Result<FileUpdate, String> {
+    let rendered = generated_file.render(file_path)?;
+    let template_hash = generated_file.hash_template(file_path)?;
+    let output_hash = hash_content(&rendered);
+    if output_hash == generated_file.output_hash {
+        if !generated_file.template_changed(&template_hash) {
+            return Ok(FileUpdate::UpToDate);
+        }
+
+        provenance.record(
+            file_path,
+            GeneratedFile {
+                template_hash,
+                ..generated_file.to_owned()
+            },
+            &rendered,
+        )?;
+        return Ok(FileUpdate::TemplateChanged);
+    }
+
+    let current = fs::read_to_string(file_path)
+        .map_err(|err| format!("Error reading {}: {}", file_path.display(), err))?;
+    let original = generated_file
+        .read_original_render()
+        .ok_or("Its original render is missing from nextbutler/renders/")?;
+
+    let (merged, update) = match diffy::merge(&original, &current, &rendered) {
+        Ok(merged) => (merged, FileUpdate::Updated),
+        Err(merged) => (merged, FileUpdate::Conflicted),
+    };
+    fs::write(file_path, merged)
+        .map_err(|err| format!("Error writing {}: {}", file_path.display(), err))?;
+
+    provenance.record(
+        file_path,
+        GeneratedFile {
+            template_hash,
+            output_hash,
+            ..generated_file.to_owned()
+        },
+        &rendered,
+    )?;
+
+    Ok(update)
+}
+
+/// Forgets the generated files removed by `nb rm`: the one at the path, with
+/// any extension, and every one inside it when it's a folder
+pub fn forget_removed_files(removed_path: &Path) -> Result<(), String> {
+    if !get_provenance_path().is_file() {
+        return Ok(());
+    }
+
+    let mut provenance = Provenance::read()?;
+    let removed_path = removed_path.clean();
+    let removed_keys: Vec<String> = provenance
+        .files
+        .keys()
+        .filter(|file_key| {
+            let file_path = Path::new(file_key);
+            file_path.starts_with(&removed_path) || file_path.with_extension("") == removed_path
+        })
+        .cloned()
+        .collect();
+    if removed_keys.is_empty() {
+        return Ok(());
+    }
+
+    for file_key in removed_keys {
+        if let Some(generated_file) = provenance.files.remove(&file_key) {
+            provenance.remove_unused_render(&generated_file.output_hash);
+        }
+    }
+    provenance.save()
+}
+
+/// Gets how a file was generated, if it was generated from a template
+pub fn get_generated_file(file_path: &Path) -> Result<Option<GeneratedFile>, String> {
+    Ok(Provenance::read()?
+        .files
+        .remove(&get_file_key(&file_path.clean())))
+}
+
+/// Unified diff between the file rendered again with the current template
+/// and the actual file. Empty if they match
+pub fn diff_generated_file(
+    file_path: &Path,
+    generated_file: &GeneratedFile,
+    colored: bool,
+) -> Result<String, String> {
+    let current = fs::read_to_string(file_path)
+        .map_err(|err| format!("Error reading {}: {}", file_path.display(), err))?;
+    let rendered = generated_file.render(file_path)?;
+    if current == rendered {
+        return Ok(String::new());
+    }
+
+    let patch = diffy::create_patch(&rendered, &current);
+    let formatter = if colored {
+        PatchFormatter::new().with_color()
+    } else {
+        PatchFormatter::new()
+    };
+    let file_key = get_file_key(file_path);
+    let diff = formatter
+        .fmt_patch(&patch)
+        .to_string()
+        .replacen("--- original", &format!("--- {} (template)", file_key), 1)
+        .replacen("+++ modified", &format!("+++ {}", file_key), 1);
+
+    Ok(diff)
+}
+
+/// Gets every generated file that doesn't match its template anymore, or
+/// whose template changed since it was rendered, with the reason
+pub fn get_drifted_files() -> Result<Vec<(String, String)>, String> {
+    let mut drifted_files = vec![];
+    for (file_key, generated_file) in Provenance::read()?.files {
+        let file_path = Path::new(&file_key);
+        let drift = if !file_path.is_file() {
+            Some(String::from("the file doesn't exist anymore"))
+        } else {
+            match generated_file.render(file_path) {
+                Ok(rendered) => {
+                    let current = fs::read_to_string(file_path)
+                        .map_err(|err| format!("Error reading {}: {}", file_key, err))?;
+                    if current != rendered {
+                        Some(String::from("it differs from its template"))
+                    } else if generated_file
+                        .template_changed(&generated_file.hash_template(file_path)?)
+                    {
+                        Some(String::from(
+                            "its template changed since it was generated. \
+                             Run `nb template update` to record it",
+                        ))
+                    } else {
+                        None
+                    }
+                }
+                Err(err) => Some(format!("its template can't be rendered: {}", err)),
+            }
+        };
+
+        if let Some(drift) = drift {
+            drifted_files.push((file_key, drift));
+        }
+    }
+
+    Ok(drifted_files)
+}
+
+/// Path of the manifest of generated files
+pub fn get_provenance_path() -> PathBuf {
+    PathBuf::from(NEXT_BUTLER_DIR).join(PROVENANCE_FILE_NAME)
+}
+
+fn get_renders_dir() -> PathBuf {
+    PathBuf::from(NEXT_BUTLER_DIR).join(RENDERS_DIR_NAME)
+}
+
+/// Key of a file inside the manifest, using "/" as separator on every
+/// platform
+fn get_file_key(file_path: &Path) -> String {
+    file_path.to_string_lossy().replace('\\', "/")
+}
+
+/// Hex encoded SHA-256 of the content
+pub fn hash_content(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
@@ -0,0 +1,339 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use handlebars::{RenderError, RenderErrorReason};
+use minijinja::ErrorKind;
+
+use crate::constants::NEXT_BUTLER_DIR;
+
+use super::{helpers::HELPER_NAMES, template_variables::TemplateVars, PARTIALS_DIR_NAME};
+
+/// Helpers that handlebars registers by default
+const BUILT_IN_HELPER_NAMES: [&str; 16] = [
+    "if", "unless", "each", "with", "lookup", "log", "eq", "ne", "gt", "gte", "lt", "lte", "and",
+    "or", "not", "len",
+];
+
+/// Content of a registered template or partial, used to locate the errors
+/// inside of its file
+pub struct TemplateErrorSource {
+    /// Path of the template's file, or "built-in template"
+    pub display_name: String,
+    /// Content that was registered
+    pub content: String,
+    /// Lines of the file before the registered content, like the
+    /// front-matter's ones
+    pub line_offset: usize,
+}
+
+/// Sources of every template and partial of a registry, by the name they
+/// were registered with
+pub type TemplateErrorSources = BTreeMap<String, TemplateErrorSource>;
+
+/// Place of a template where an error happened
+#[derive(Debug)]
+pub struct TemplateErrorLocation {
+    /// Path of the template's file, or "built-in template"
+    pub template: String,
+    /// Line of the template's file, starting at 1
+    pub line: usize,
+    /// Column of the line, starting at 1
+    pub column: usize,
+    /// Content of the line, if it's known
+    pub excerpt: Option<String>,
+}
+
+/// Error compiling or rendering a template
+#[derive(Debug)]
+pub struct TemplateError {
+    pub message: String,
+    pub location: Option<TemplateErrorLocation>,
+    /// How the error could be fixed
+    pub hint: Option<String>,
+}
+
+impl std::error::Error for TemplateError {}
+
+impl TemplateError {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            location: None,
+            hint: None,
+        }
+    }
+
+    /// Error compiling a template or a partial, registered with the given
+    /// source
+    pub fn from_compile_error(
+        err: &handlebars::TemplateError,
+        source: &TemplateErrorSource,
+    ) -> Self {
+        Self {
+            message: format!("Invalid template: {}", err.reason()),
+            location: err
+                .pos()
+                .map(|(line, column)| locate(source, line, column)),
+            hint: None,
+        }
+    }
+
+    /// Error rendering the template registered as `template_name`, which may
+    /// have happened inside one of its partials
+    pub fn from_render_error(
+        err: &RenderError,
+        template_name: &str,
+        sources: &TemplateErrorSources,
+        template_vars: &TemplateVars,
+    ) -> Self {
+        let failed_template = err.template_name.as_deref().unwrap_or(template_name);
+        let location = match (sources.get(failed_template), err.line_no, err.column_no) {
+            (Some(source), Some(line), Some(column)) => Some(locate(source, line, column)),
+            _ => None,
+        };
+        let failed_template = get_display_name(failed_template, sources);
+
+        let (message, hint) = match err.reason() {
+            RenderErrorReason::PartialNotFound(partial_name) => {
+                missing_partial(partial_name, failed_template, sources, |similar| {
+                    format!("{{{{> {}}}}}", similar)
+                })
+            }
+            RenderErrorReason::MissingVariable(var_path) => missing_variable(
+                var_path.as_deref().unwrap_or_default(),
+                template_vars,
+                |similar| format!("{{{{{}}}}}", similar),
+            ),
+            RenderErrorReason::ParamNotFoundForName(helper, param) => (
+                format!(
+                    "Undefined value for the '{}' parameter of the '{}' helper. Available variables: {}",
+                    param,
+                    helper,
+                    get_available_vars(template_vars)
+                ),
+                find_similar(param, template_vars.keys().map(String::as_str))
+                    .map(|similar| format!("did you mean `{}`?", similar)),
+            ),
+            RenderErrorReason::HelperNotFound(helper) => unknown_helper(helper),
+            reason => (format!("Error rendering {}: {}", failed_template, reason), None),
+        };
+
+        Self {
+            message,
+            location,
+            hint,
+        }
+    }
+
+    /// Error compiling or rendering a MiniJinja template, which may have
+    /// happened inside one of its partials
+    pub fn from_jinja_error(
+        err: &minijinja::Error,
+        sources: &TemplateErrorSources,
+        template_vars: &TemplateVars,
+    ) -> Self {
+        let failed_template = err.name().unwrap_or_default();
+        let source = sources.get(failed_template);
+        // Part of the template that failed, like the name of an undefined
+        // variable
+        let failed_code = source
+            .zip(err.range())
+            .and_then(|(source, range)| source.content.get(range))
+            .unwrap_or_default()
+            .trim();
+        let location = source.zip(err.line()).map(|(source, line)| {
+            let column = match err.range() {
+                Some(range) => {
+                    let line_start = source.content[..range.start]
+                        .rfind('\n')
+                        .map_or(0, |line_break| line_break + 1);
+                    source.content[line_start..range.start].chars().count() + 1
+                }
+                None => 1,
+            };
+            locate(source, line, column)
+        });
+        let failed_template = get_display_name(failed_template, sources);
+        let detail = err.detail().unwrap_or_default();
+
+        let (message, hint) = match err.kind() {
+            ErrorKind::UndefinedError if is_identifier(failed_code) => {
+                missing_variable(failed_code, template_vars, |similar| {
+                    format!("{{{{ {} }}}}", similar)
+                })
+            }
+            ErrorKind::UnknownFilter | ErrorKind::UnknownFunction => {
+                unknown_helper(detail.split(' ').next_back().unwrap_or(failed_code))
+            }
+            ErrorKind::TemplateNotFound => missing_partial(
+                detail.split('"').nth(1).unwrap_or(detail),
+                failed_template,
+                sources,
+                |similar| format!("{{% include \"{}\" %}}", similar),
+            ),
+            ErrorKind::SyntaxError => (format!("Invalid template: {}", detail), None),
+            kind => (
+                format!("Error rendering {}: {}. {}", failed_template, kind, detail),
+                None,
+            ),
+        };
+
+        Self {
+            message,
+            location,
+            hint,
+        }
+    }
+}
+
+impl From<String> for TemplateError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<TemplateError> for String {
+    fn from(err: TemplateError) -> Self {
+        err.to_string()
+    }
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+
+        if let Some(location) = &self.location {
+            let line_no = location.line.to_string();
+            let gutter = " ".repeat(line_no.len());
+            write!(
+                f,
+                "\n{}--> {}:{}:{}",
+                gutter, location.template, location.line, location.column
+            )?;
+            if let Some(excerpt) = &location.excerpt {
+                write!(
+                    f,
+                    "\n{} |\n{} | {}\n{} | {}^",
+                    gutter,
+                    line_no,
+                    excerpt,
+                    gutter,
+                    " ".repeat(location.column.saturating_sub(1))
+                )?;
+            }
+        }
+
+        if let Some(hint) = &self.hint {
+            write!(f, "\nhint: {}", hint)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Path of the registered template or partial, for the messages
+fn get_display_name<'a>(tmpl_name: &'a str, sources: &'a TemplateErrorSources) -> &'a str {
+    sources
+        .get(tmpl_name)
+        .map_or(tmpl_name, |source| source.display_name.as_str())
+}
+
+fn get_available_vars(template_vars: &TemplateVars) -> String {
+    if template_vars.is_empty() {
+        String::from("none")
+    } else {
+        template_vars
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
+/// Message and hint for an undefined variable. `usage` shows how to use the
+/// suggested variable
+fn missing_variable(
+    var_name: &str,
+    template_vars: &TemplateVars,
+    usage: impl Fn(&str) -> String,
+) -> (String, Option<String>) {
+    (
+        format!(
+            "Undefined variable '{}'. Available variables: {}",
+            var_name,
+            get_available_vars(template_vars)
+        ),
+        find_similar(var_name, template_vars.keys().map(String::as_str))
+            .map(|similar| format!("did you mean `{}`?", usage(similar))),
+    )
+}
+
+/// Message and hint for a partial that doesn't exist. `usage` shows how to
+/// include the suggested partial
+fn missing_partial(
+    partial_name: &str,
+    failed_template: &str,
+    sources: &TemplateErrorSources,
+    usage: impl Fn(&str) -> String,
+) -> (String, Option<String>) {
+    (
+        format!(
+            "Partial '{}' used by {} couldn't be found",
+            partial_name, failed_template
+        ),
+        Some(
+            match find_similar(partial_name, sources.keys().map(String::as_str)) {
+                Some(similar) => format!("did you mean `{}`?", usage(similar)),
+                None => format!(
+                    "partials must be placed inside the {} folder of a templates directory, like {}",
+                    PARTIALS_DIR_NAME,
+                    PathBuf::from(NEXT_BUTLER_DIR)
+                        .join("templates/")
+                        .join(PARTIALS_DIR_NAME)
+                        .display()
+                ),
+            },
+        ),
+    )
+}
+
+fn unknown_helper(helper: &str) -> (String, Option<String>) {
+    (
+        format!("Unknown helper '{}'", helper),
+        find_similar(helper, HELPER_NAMES.into_iter().chain(BUILT_IN_HELPER_NAMES))
+            .map(|similar| format!("did you mean `{}`?", similar)),
+    )
+}
+
+fn is_identifier(code: &str) -> bool {
+    !code.is_empty() && code.chars().all(|ch| ch.is_alphanumeric() || ch == '_')
+}
+
+/// Converts a position inside the registered content to a position inside
+/// the template's file
+fn locate(source: &TemplateErrorSource, line: usize, column: usize) -> TemplateErrorLocation {
+    TemplateErrorLocation {
+        template: source.display_name.to_owned(),
+        line: line + source.line_offset,
+        column,
+        excerpt: source
+            .content
+            .lines()
+            .nth(line.saturating_sub(1))
+            .map(|excerpt| excerpt.trim_end().to_owned()),
+    }
+}
+
+/// Finds the candidate most similar to a misspelled name, if any is close
+/// enough
+fn find_similar<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (candidate, strsim::jaro_winkler(name, candidate)))
+        .filter(|(_, similarity)| *similarity >= 0.8)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(candidate, _)| candidate)
+}
@@ -0,0 +1,85 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::NEXT_BUTLER_DIR,
+    helpers::{file_helper, project_helper},
+    user_config::UserConfig,
+    CreateableFileType,
+};
+
+/// Places where templates are searched, from the highest to the lowest
+/// precedence
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TemplateLayer {
+    /// nextbutler/templates/ inside the project
+    Project,
+    /// nextbutler/templates/ at the root of the workspace holding the project
+    Workspace,
+    /// next-butler/templates/ inside the user's configuration directory
+    User,
+    /// One of the directories listed in the `template_dirs` configuration
+    Extra,
+    /// The templates shipped with next-butler
+    BuiltIn,
+    /// An installed template pack, referred to explicitly with
+    /// "<pack>/<template>"
+    Pack(String),
+}
+
+impl Display for TemplateLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TemplateLayer::Project => "project",
+            TemplateLayer::Workspace => "workspace",
+            TemplateLayer::User => "user",
+            TemplateLayer::Extra => "extra directory",
+            TemplateLayer::BuiltIn => "built-in",
+            TemplateLayer::Pack(pack_name) => return write!(f, "'{}' pack", pack_name),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Gets the base directory of every layer that stores templates as files,
+/// from the highest to the lowest precedence. Directories that don't exist
+/// are included too, so they can be listed in error messages
+pub fn get_template_layers() -> Result<Vec<(TemplateLayer, PathBuf)>, String> {
+    let mut layers = vec![(
+        TemplateLayer::Project,
+        PathBuf::from(NEXT_BUTLER_DIR).join("templates/"),
+    )];
+
+    if let Some(workspace_root) = project_helper::get_workspace_root() {
+        layers.push((
+            TemplateLayer::Workspace,
+            workspace_root.join(NEXT_BUTLER_DIR).join("templates/"),
+        ));
+    }
+
+    if let Some(user_config_dir) = file_helper::get_user_config_dir() {
+        layers.push((TemplateLayer::User, user_config_dir.join("templates/")));
+    }
+
+    for extra_dir in UserConfig::get()?.template_dirs.unwrap_or_default() {
+        layers.push((TemplateLayer::Extra, PathBuf::from(extra_dir)));
+    }
+
+    Ok(layers)
+}
+
+/// Name of the directory, inside a layer, holding the templates of the given
+/// kind of file
+pub fn get_kind_dir_name(file: &CreateableFileType) -> &'static str {
+    match file {
+        CreateableFileType::Page => "pages/",
+        CreateableFileType::ApiPage => "api-pages/",
+        CreateableFileType::Stylesheet => "stylesheets/",
+        CreateableFileType::Component => "components/",
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::NEXT_BUTLER_DIR, helpers::file_helper};
+
+/// Name of the file describing a template pack, located at its root
+pub const PACK_MANIFEST_FILE_NAME: &str = "nextbutler-pack.json";
+
+/// Folders that a template pack can provide, named like the ones inside a
+/// templates directory
+pub const PACK_KINDS: [&str; 5] = ["pages", "api-pages", "components", "stylesheets", "partials"];
+
+/// Description of a template pack
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PackManifest {
+    /// Name used to refer to the pack's templates, like "acme" in "acme/card"
+    pub name: String,
+    pub version: String,
+    /// Kinds of templates provided by the pack ("pages", "components", ...)
+    pub kinds: Vec<String>,
+}
+
+impl PackManifest {
+    fn read(pack_dir: &Path) -> Result<Self, String> {
+        let manifest_path = pack_dir.join(PACK_MANIFEST_FILE_NAME);
+        let manifest: Self = file_helper::json_file_to_struct(&manifest_path)
+            .map_err(|err| format!("Invalid pack manifest {}: {}", manifest_path.display(), err))?;
+
+        if !is_valid_pack_name(&manifest.name) {
+            return Err(format!(
+                "Invalid pack name '{}'. It can only contain letters, numbers, '-', '_' and '.'",
+                manifest.name
+            ));
+        }
+        if let Some(unknown_kind) = manifest
+            .kinds
+            .iter()
+            .find(|kind| !PACK_KINDS.contains(&kind.as_str()))
+        {
+            return Err(format!(
+                "Unknown template kind '{}' in pack '{}'. Valid kinds are: {}",
+                unknown_kind,
+                manifest.name,
+                PACK_KINDS.join(", ")
+            ));
+        }
+
+        Ok(manifest)
+    }
+}
+
+fn is_valid_pack_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ['-', '_', '.'].contains(&ch))
+}
+
+/// Directory where the packs are installed, each one in a folder named after
+/// the pack
+pub fn get_packs_dir() -> PathBuf {
+    PathBuf::from(NEXT_BUTLER_DIR).join("packs/")
+}
+
+/// Gets the manifest and the directory of an installed pack, if it exists
+pub fn get_installed_pack(pack_name: &str) -> Result<Option<(PackManifest, PathBuf)>, String> {
+    if !is_valid_pack_name(pack_name) {
+        return Ok(None);
+    }
+
+    let pack_dir = get_packs_dir().join(pack_name);
+    if !pack_dir.join(PACK_MANIFEST_FILE_NAME).is_file() {
+        return Ok(None);
+    }
+
+    PackManifest::read(&pack_dir).map(|manifest| Some((manifest, pack_dir)))
+}
+
+/// Gets every installed pack
+pub fn get_installed_packs() -> Result<Vec<(PackManifest, PathBuf)>, String> {
+    let packs_dir = get_packs_dir();
+    let mut packs = vec![];
+    if !packs_dir.is_dir() {
+        return Ok(packs);
+    }
+
+    for dir_entry in fs::read_dir(&packs_dir).map_err(|err| err.to_string())? {
+        let pack_name = dir_entry
+            .map_err(|err| err.to_string())?
+            .file_name()
+            .to_string_lossy()
+            .to_string();
+        if let Some(pack) = get_installed_pack(&pack_name)? {
+            packs.push(pack);
+        }
+    }
+
+    packs.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+    Ok(packs)
+}
+
+/// Installs the pack located in a directory or a .tar.gz archive, replacing
+/// any installed version of it. Returns its manifest
+pub fn install_pack(source: impl AsRef<Path>) -> Result<PackManifest, String> {
+    let source = source.as_ref();
+    let staging_dir = get_packs_dir().join(".installing/");
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir).map_err(|err| err.to_string())?;
+    }
+    fs::create_dir_all(&staging_dir)
+        .map_err(|err| format!("Error creating {}: {}", staging_dir.display(), err))?;
+
+    let result = stage_pack(source, &staging_dir).and_then(|staged_pack_dir| {
+        let manifest = PackManifest::read(&staged_pack_dir)?;
+        let pack_dir = get_packs_dir().join(&manifest.name);
+        if pack_dir.exists() {
+            fs::remove_dir_all(&pack_dir)
+                .map_err(|err| format!("Error removing {}: {}", pack_dir.display(), err))?;
+        }
+        fs::rename(&staged_pack_dir, &pack_dir)
+            .map_err(|err| format!("Error installing the pack: {}", err))?;
+        Ok(manifest)
+    });
+
+    let _ = fs::remove_dir_all(&staging_dir);
+    result
+}
+
+/// Copies or extracts the pack inside the staging directory, returning the
+/// folder that holds the pack's manifest
+fn stage_pack(source: &Path, staging_dir: &Path) -> Result<PathBuf, String> {
+    let staged_pack_dir = staging_dir.join("pack/");
+
+    if source.is_dir() {
+        copy_dir_all(source, &staged_pack_dir)?;
+        return Ok(staged_pack_dir);
+    }
+
+    if !source.is_file() {
+        return Err(format!("Couldn't find the pack {}", source.display()));
+    }
+
+    let archive_file = File::open(source)
+        .map_err(|err| format!("Error opening {}: {}", source.display(), err))?;
+    tar::Archive::new(GzDecoder::new(archive_file))
+        .unpack(&staged_pack_dir)
+        .map_err(|err| format!("Error extracting {}: {}", source.display(), err))?;
+
+    // Archives can hold the pack at their root or inside a single folder
+    if staged_pack_dir.join(PACK_MANIFEST_FILE_NAME).is_file() {
+        return Ok(staged_pack_dir);
+    }
+    let mut entries = fs::read_dir(&staged_pack_dir)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path());
+    match (entries.next(), entries.next()) {
+        (Some(single_dir), None) if single_dir.join(PACK_MANIFEST_FILE_NAME).is_file() => {
+            Ok(single_dir)
+        }
+        _ => Err(format!(
+            "{} is not a template pack: {} is missing",
+            source.display(),
+            PACK_MANIFEST_FILE_NAME
+        )),
+    }
+}
+
+fn copy_dir_all(from: &Path, to: &Path) -> Result<(), String> {
+    for file in file_helper::get_files_recursively(from)? {
+        let relative_path = file.strip_prefix(from).map_err(|err| err.to_string())?;
+        let destination = to.join(relative_path);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        fs::copy(&file, &destination)
+            .map_err(|err| format!("Error copying {}: {}", file.display(), err))?;
+    }
+
+    Ok(())
+}
+
+/// Builds a .tar.gz pack from a templates directory, including every kind of
+/// template it has. Returns the manifest written inside the archive
+pub fn build_pack(
+    templates_dir: impl AsRef<Path>,
+    name: &str,
+    version: &str,
+    output: impl AsRef<Path>,
+) -> Result<PackManifest, String> {
+    let templates_dir = templates_dir.as_ref();
+    let output = output.as_ref();
+    if !is_valid_pack_name(name) {
+        return Err(format!(
+            "Invalid pack name '{}'. It can only contain letters, numbers, '-', '_' and '.'",
+            name
+        ));
+    }
+
+    let kinds: Vec<String> = PACK_KINDS
+        .iter()
+        .filter(|kind| templates_dir.join(kind).is_dir())
+        .map(|kind| kind.to_string())
+        .collect();
+    if kinds.is_empty() {
+        return Err(format!("There are no templates in {}", templates_dir.display()));
+    }
+
+    let manifest = PackManifest {
+        name: name.to_owned(),
+        version: version.to_owned(),
+        kinds,
+    };
+    let manifest_content = serde_json::to_vec_pretty(&manifest).map_err(|err| err.to_string())?;
+
+    let archive_file = File::create(output)
+        .map_err(|err| format!("Error creating {}: {}", output.display(), err))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(archive_file, Compression::default()));
+
+    let mut manifest_header = tar::Header::new_gnu();
+    manifest_header.set_size(manifest_content.len() as u64);
+    manifest_header.set_mode(0o644);
+    manifest_header.set_cksum();
+    archive
+        .append_data(&mut manifest_header, PACK_MANIFEST_FILE_NAME, manifest_content.as_slice())
+        .map_err(|err| err.to_string())?;
+
+    for kind in &manifest.kinds {
+        archive
+            .append_dir_all(kind, templates_dir.join(kind))
+            .map_err(|err| format!("Error adding the {} templates: {}", kind, err))?;
+    }
+
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|err| format!("Error writing {}: {}", output.display(), err))?;
+
+    Ok(manifest)
+}
@@ -1,8 +1,83 @@
-/// Struct that holds all the template variables that should be used
-/// when formatting the content
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde_json::Value;
+
+/// Variables used when rendering a template
+pub type TemplateVars = BTreeMap<String, String>;
+
+/// Builds the variables that will be used to render a template.
 ///
-/// For now it only has the name, but I think it may have more in the future
-pub struct TemplateVariables<'a> {
-    // Not Option, this has to be set
-    pub name: &'a str,
+/// Sources are applied from the lowest to the highest precedence:
+/// - The built-in variables (like `name`)
+/// - The variables defined in the configuration file
+/// - The variables defined in the JSON file given with `--vars`
+/// - The variables given with `--var key=value`
+pub fn merge_template_vars(
+    builtin_vars: TemplateVars,
+    config_vars: Option<&TemplateVars>,
+    vars_file: Option<&String>,
+    var_args: &[String],
+) -> Result<TemplateVars, String> {
+    let mut template_vars = builtin_vars;
+
+    if let Some(config_vars) = config_vars {
+        template_vars.extend(config_vars.clone());
+    }
+
+    if let Some(vars_file) = vars_file {
+        template_vars.extend(read_vars_file(vars_file)?);
+    }
+
+    for var_arg in var_args {
+        let (key, value) = parse_var_arg(var_arg)?;
+        template_vars.insert(key, value);
+    }
+
+    Ok(template_vars)
+}
+
+/// Parses a "key=value" pair. The value can contain '=' characters
+pub fn parse_var_arg(var_arg: &str) -> Result<(String, String), String> {
+    match var_arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_owned(), value.to_owned()))
+        }
+        _ => Err(format!(
+            "Invalid variable '{}'. Variables must follow the format key=value",
+            var_arg
+        )),
+    }
+}
+
+/// Reads a JSON file containing an object of variables.
+///
+/// Strings are used as is, while numbers and booleans are converted to their
+/// JSON representation
+fn read_vars_file(vars_file: impl AsRef<Path>) -> Result<TemplateVars, String> {
+    let vars_file = vars_file.as_ref();
+    let content = fs::read_to_string(vars_file)
+        .map_err(|err| format!("Couldn't read {}: {}", vars_file.display(), err))?;
+    let json: Value = serde_json::from_str(&content)
+        .map_err(|err| format!("Invalid variables file {}: {}", vars_file.display(), err))?;
+
+    let Value::Object(json_vars) = json else {
+        return Err(format!(
+            "Invalid variables file {}: it must contain an object",
+            vars_file.display()
+        ));
+    };
+
+    json_vars
+        .into_iter()
+        .map(|(key, value)| match value {
+            Value::String(value) => Ok((key, value)),
+            Value::Null => Ok((key, String::new())),
+            Value::Bool(_) | Value::Number(_) => Ok((key, value.to_string())),
+            Value::Array(_) | Value::Object(_) => Err(format!(
+                "Invalid variables file {}: the value of '{}' must be a string, number or boolean",
+                vars_file.display(),
+                key
+            )),
+        })
+        .collect()
 }
@@ -0,0 +1,424 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use path_clean::PathClean;
+use serde_json::{Map, Value};
+
+use crate::helpers::{file_helper, project_helper};
+
+use super::{
+    config_schema::get_settings_tree,
+    config_source::{ConfigFormat, ConfigSource},
+    config_validation::validate_config_layers,
+    UserConfig,
+};
+
+/// Name of the user-level configuration file, inside the user's
+/// configuration directory
+const USER_CONFIG_FILE_NAME: &str = "config.json";
+
+/// Key of a configuration referring to the one it's based on
+const EXTENDS_KEY: &str = "extends";
+
+/// File used when `extends` refers to a directory, like a package
+const EXTENDED_DIR_CONFIG_FILE_NAME: &str = "nextbutler.json";
+
+/// Prefix of the environment variables overriding the settings
+const ENV_VAR_PREFIX: &str = "NB_";
+
+/// File overriding the configuration for everything created inside its
+/// folder
+const DIR_CONFIG_FILE_NAME: &str = ".nextbutler.json";
+
+/// Key of the named configurations that can overlay the base one
+pub const PRESETS_KEY: &str = "presets";
+
+/// Key of the preset applied when none is chosen with --preset
+const DEFAULT_PRESET_KEY: &str = "default_preset";
+
+/// Preset chosen with --preset
+static SELECTED_PRESET: OnceLock<String> = OnceLock::new();
+
+/// Where a layer of the configuration comes from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigLayerKind {
+    /// The default configuration, used when there is no configuration file
+    Default,
+    /// The user-level configuration
+    User,
+    /// The configuration at the root of the workspace
+    Workspace,
+    /// The project's configuration
+    Project,
+    /// A configuration extended by another one
+    Extended,
+    /// The preset chosen with --preset, or the default one
+    Preset,
+    /// A .nextbutler.json inside one of the folders holding the new file
+    Directory,
+    /// An environment variable, like NB_NEW_PAGE_TYPESCRIPT
+    Environment,
+}
+
+/// One of the configurations merged into the final one
+#[derive(Debug)]
+pub struct ConfigLayer {
+    pub kind: ConfigLayerKind,
+    /// File or package.json key the layer was read from
+    pub source: String,
+    /// Where the configuration was read from, if it's a file
+    pub config_source: Option<ConfigSource>,
+    pub config: Value,
+}
+
+impl Display for ConfigLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ConfigLayerKind::User => "user",
+            ConfigLayerKind::Workspace => "workspace",
+            ConfigLayerKind::Project => "project",
+            ConfigLayerKind::Default => "default",
+            ConfigLayerKind::Extended => "extended",
+            ConfigLayerKind::Preset => "preset",
+            ConfigLayerKind::Directory => "directory",
+            ConfigLayerKind::Environment => "environment",
+        };
+        write!(f, "{} ({})", kind, self.source)
+    }
+}
+
+/// Chooses the preset overlaying the configuration, instead of the default
+/// one
+pub fn select_preset(preset_name: &str) {
+    let _ = SELECTED_PRESET.set(preset_name.to_owned());
+}
+
+/// Gets every layer of the configuration, from the lowest to the highest
+/// precedence: the user-level configuration, the workspace's one, the
+/// project's one, the selected preset, the .nextbutler.json files from the
+/// root down to `target_dir` (if given) and the environment variables. Each
+/// configuration comes after the ones it extends. Without configuration
+/// files, the default configuration is the first layer.
+///
+/// Fails if any layer has unknown keys or values of the wrong type
+pub fn get_config_layers(target_dir: Option<&Path>) -> Result<Vec<ConfigLayer>, String> {
+    let mut layers = vec![];
+
+    if let Some(user_config_path) = get_user_config_path().filter(|path| path.is_file()) {
+        let format = ConfigFormat::from_path(&user_config_path).unwrap_or(ConfigFormat::Json);
+        push_layer(
+            &mut layers,
+            ConfigLayerKind::User,
+            ConfigSource::File(user_config_path, format),
+            &mut vec![],
+        )?;
+    }
+    if let Some(workspace_root) = project_helper::get_workspace_root() {
+        if let Some(config_source) = ConfigSource::find(workspace_root)? {
+            push_layer(&mut layers, ConfigLayerKind::Workspace, config_source, &mut vec![])?;
+        }
+    }
+    if let Some(config_source) = ConfigSource::find(Path::new(""))? {
+        push_layer(&mut layers, ConfigLayerKind::Project, config_source, &mut vec![])?;
+    }
+
+    if layers.is_empty() {
+        layers.push(ConfigLayer {
+            kind: ConfigLayerKind::Default,
+            source: String::from("built-in"),
+            config_source: None,
+            config: serde_json::to_value(UserConfig::get_default())
+                .map_err(|err| err.to_string())?,
+        });
+    }
+    if let Some(preset_layer) = get_preset_layer(&layers)? {
+        layers.push(preset_layer);
+    }
+    if let Some(target_dir) = target_dir {
+        push_dir_layers(&mut layers, target_dir)?;
+    }
+    layers.extend(get_env_layers());
+    validate_config_layers(&layers)?;
+
+    Ok(layers)
+}
+
+/// Gets the layer of the preset chosen with --preset or, if none was
+/// chosen, of the `default_preset`. Presets can be defined in any of the
+/// given layers
+fn get_preset_layer(layers: &[ConfigLayer]) -> Result<Option<ConfigLayer>, String> {
+    let (config, _) = merge_config_layers(layers);
+    let preset_name = match (SELECTED_PRESET.get(), config.get(DEFAULT_PRESET_KEY)) {
+        (Some(preset_name), _) => preset_name.to_owned(),
+        (None, Some(Value::String(preset_name))) => preset_name.to_owned(),
+        (None, None | Some(Value::Null)) => return Ok(None),
+        (None, Some(_)) => {
+            return Err(format!(
+                "Invalid configuration: \"{}\" must be the name of a preset",
+                DEFAULT_PRESET_KEY
+            ))
+        }
+    };
+
+    let presets = config
+        .get(PRESETS_KEY)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let Some(mut preset) = presets.get(&preset_name).cloned() else {
+        let preset_names = match presets.is_empty() {
+            true => String::from("none"),
+            false => presets.keys().cloned().collect::<Vec<String>>().join(", "),
+        };
+        return Err(format!(
+            "Unknown preset '{}'. The configuration's presets are: {}",
+            preset_name, preset_names
+        ));
+    };
+    if let Some(preset) = preset.as_object_mut() {
+        preset.remove(PRESETS_KEY);
+        preset.remove(DEFAULT_PRESET_KEY);
+    }
+
+    Ok(Some(ConfigLayer {
+        kind: ConfigLayerKind::Preset,
+        source: preset_name,
+        config_source: None,
+        config: preset,
+    }))
+}
+
+/// Adds the .nextbutler.json of every folder from the project's root down to
+/// the given one, which is relative to the root, so the nearest one wins
+fn push_dir_layers(layers: &mut Vec<ConfigLayer>, target_dir: &Path) -> Result<(), String> {
+    if target_dir.is_absolute() {
+        return Ok(());
+    }
+
+    let target_dir = target_dir.clean();
+    let dir_config_paths: Vec<PathBuf> = target_dir
+        .ancestors()
+        .map(|dir| dir.join(DIR_CONFIG_FILE_NAME))
+        .filter(|dir_config_path| dir_config_path.is_file())
+        .collect();
+
+    for dir_config_path in dir_config_paths.into_iter().rev() {
+        push_layer(
+            layers,
+            ConfigLayerKind::Directory,
+            ConfigSource::File(dir_config_path, ConfigFormat::Json),
+            &mut vec![],
+        )?;
+    }
+    Ok(())
+}
+
+/// Gets a layer for every environment variable overriding a setting. Their
+/// names are the path of the setting in uppercase, joined by "_" and
+/// prefixed with "NB_", like NB_NEW_COMPONENT_FOLDER for `new.component.folder`.
+/// Their values are read as JSON, or as strings if they are not valid JSON
+fn get_env_layers() -> Vec<ConfigLayer> {
+    let mut env_vars: Vec<(String, String)> = env::vars()
+        .filter(|(var_name, _)| var_name.starts_with(ENV_VAR_PREFIX))
+        .collect();
+    env_vars.sort();
+
+    env_vars
+        .into_iter()
+        .filter_map(|(var_name, value)| {
+            let setting_path = get_setting_path(get_settings_tree(), &var_name[ENV_VAR_PREFIX.len()..])?;
+            let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+            let config = setting_path
+                .into_iter()
+                .rev()
+                .fold(value, |value, key| Value::Object(Map::from_iter([(key, value)])));
+
+            Some(ConfigLayer {
+                kind: ConfigLayerKind::Environment,
+                source: var_name,
+                config_source: None,
+                config,
+            })
+        })
+        .collect()
+}
+
+/// Finds the setting an environment variable name (without its prefix)
+/// refers to, matching its parts with the keys of the configuration tree
+/// (see [get_settings_tree]).
+/// Keys can contain "_" too, like `page_router`. Below a setting without
+/// known keys, like `variables`, the rest of the name is a single lowercase
+/// key
+fn get_setting_path(config_tree: &Value, var_name: &str) -> Option<Vec<String>> {
+    let Value::Object(settings) = config_tree else {
+        return match config_tree {
+            Value::Null if !var_name.is_empty() => Some(vec![var_name.to_lowercase()]),
+            _ => None,
+        };
+    };
+
+    settings.iter().find_map(|(key, subtree)| {
+        let key_name = key.to_uppercase();
+        if var_name == key_name {
+            return Some(vec![key.to_owned()]);
+        }
+
+        let rest = var_name.strip_prefix(&key_name)?.strip_prefix('_')?;
+        let mut setting_path = get_setting_path(subtree, rest)?;
+        setting_path.insert(0, key.to_owned());
+        Some(setting_path)
+    })
+}
+
+/// Path of the user-level configuration
+pub fn get_user_config_path() -> Option<PathBuf> {
+    file_helper::get_user_config_dir().map(|config_dir| config_dir.join(USER_CONFIG_FILE_NAME))
+}
+
+/// Adds the layer read from the source, after the ones it extends.
+/// `extended_paths` holds the files of the current chain, to detect cycles
+fn push_layer(
+    layers: &mut Vec<ConfigLayer>,
+    kind: ConfigLayerKind,
+    config_source: ConfigSource,
+    extended_paths: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let mut config = config_source.read()?;
+    let extends = match config.as_object_mut() {
+        Some(config) => {
+            config.remove("workspace");
+            config.remove(EXTENDS_KEY)
+        }
+        None => None,
+    };
+
+    if let Some(extends) = extends {
+        let Value::String(extends) = extends else {
+            return Err(format!(
+                "Invalid {}: \"{}\" must be a path",
+                config_source, EXTENDS_KEY
+            ));
+        };
+        let base_dir = match &config_source {
+            ConfigSource::File(config_path, _) => config_path.parent(),
+            ConfigSource::PackageJson(package_json_path) => package_json_path.parent(),
+        }
+        .unwrap_or(Path::new(""));
+
+        let extended_path = resolve_extends(base_dir, &extends).ok_or(format!(
+            "Couldn't find the configuration '{}' extended by {}",
+            extends, config_source
+        ))?;
+        if extended_paths.contains(&extended_path) {
+            return Err(format!(
+                "The configuration {} extends itself",
+                extended_path.display()
+            ));
+        }
+        extended_paths.push(extended_path.to_owned());
+
+        let format = ConfigFormat::from_path(&extended_path).unwrap_or(ConfigFormat::Json);
+        push_layer(
+            layers,
+            ConfigLayerKind::Extended,
+            ConfigSource::File(extended_path, format),
+            extended_paths,
+        )?;
+    }
+
+    layers.push(ConfigLayer {
+        kind,
+        source: config_source.to_string(),
+        config_source: Some(config_source),
+        config,
+    });
+    Ok(())
+}
+
+/// Finds the configuration referred to by `extends`. Relative paths start
+/// at the directory of the configuration extending it, while other names are
+/// searched inside the node_modules/ folders above it, like Node.js does.
+/// Directories, like a package's one, must hold a nextbutler.json
+fn resolve_extends(base_dir: &Path, extends: &str) -> Option<PathBuf> {
+    let candidates: Vec<PathBuf> = if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        vec![base_dir.join(extends)]
+    } else {
+        base_dir
+            .ancestors()
+            .map(|dir| dir.join("node_modules").join(extends))
+            .collect()
+    };
+
+    candidates
+        .into_iter()
+        .map(|candidate| match candidate.is_dir() {
+            true => candidate.join(EXTENDED_DIR_CONFIG_FILE_NAME),
+            false => candidate,
+        })
+        .find(|candidate| candidate.is_file())
+        .map(|candidate| candidate.clean())
+}
+
+/// Merges the layers, from the lowest to the highest precedence. Returns the
+/// final configuration and the layer each value came from, by its path (like
+/// "new.component.jsx")
+pub fn merge_config_layers(layers: &[ConfigLayer]) -> (Value, BTreeMap<String, String>) {
+    let mut config = Value::Object(Default::default());
+    let mut origins = BTreeMap::new();
+    for layer in layers {
+        merge_config(
+            &mut config,
+            layer.config.to_owned(),
+            "",
+            &layer.to_string(),
+            &mut origins,
+        );
+    }
+
+    (config, origins)
+}
+
+/// Merges the `overrides` configuration into `base`. Objects are merged key
+/// by key, while any other value replaces the previous one and records its
+/// origin
+fn merge_config(
+    base: &mut Value,
+    overrides: Value,
+    key_path: &str,
+    origin: &str,
+    origins: &mut BTreeMap<String, String>,
+) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                let value_path = match key_path {
+                    "" => key.to_owned(),
+                    key_path => format!("{}.{}", key_path, key),
+                };
+                let base_value = base.entry(key).or_insert(Value::Null);
+                merge_config(base_value, value, &value_path, origin, origins);
+            }
+        }
+        (base, overrides) => {
+            // The values that were inside the replaced one don't exist anymore
+            let nested_prefix = format!("{}.", key_path);
+            origins.retain(|value_path, _| !value_path.starts_with(&nested_prefix));
+            if !overrides.is_object() {
+                origins.insert(key_path.to_owned(), origin.to_owned());
+            }
+            *base = match overrides {
+                Value::Object(overrides) => {
+                    let mut merged = Value::Object(Default::default());
+                    merge_config(&mut merged, Value::Object(overrides), key_path, origin, origins);
+                    merged
+                }
+                overrides => overrides,
+            };
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+
+use super::UserConfig;
+
+/// Every key the configuration can have, read from its JSON schema. Sections
+/// (like `new.page`) hold their keys, and any other setting (like
+/// `new.page.jsx` or `new.page.variables`) is null
+pub fn get_settings_tree() -> &'static Value {
+    static SETTINGS_TREE: OnceLock<Value> = OnceLock::new();
+
+    SETTINGS_TREE.get_or_init(|| {
+        let schema = serde_json::to_value(schemars::schema_for!(UserConfig)).unwrap_or_default();
+        get_schema_settings(&schema, &schema)
+    })
+}
+
+fn get_schema_settings(schema: &Value, root_schema: &Value) -> Value {
+    let schema = resolve_schema_ref(schema, root_schema);
+
+    // Optional sections are one of the section or null
+    if let Some(variants) = schema.get("anyOf").and_then(Value::as_array) {
+        return variants
+            .iter()
+            .map(|variant| get_schema_settings(variant, root_schema))
+            .find(Value::is_object)
+            .unwrap_or(Value::Null);
+    }
+
+    match schema.get("properties").and_then(Value::as_object) {
+        Some(properties) => Value::Object(
+            properties
+                .iter()
+                .map(|(key, property)| (key.to_owned(), get_schema_settings(property, root_schema)))
+                .collect::<Map<String, Value>>(),
+        ),
+        None => Value::Null,
+    }
+}
+
+/// Schema a `$ref` (like "#/$defs/New") points to, or the given schema if it
+/// isn't one
+fn resolve_schema_ref<'a>(schema: &'a Value, root_schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|schema_ref| schema_ref.strip_prefix('#'))
+        .and_then(|pointer| root_schema.pointer(pointer))
+        .unwrap_or(schema)
+}
@@ -0,0 +1,142 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::constants::NEXT_BUTLER_DIR;
+
+/// Key of the package.json holding the configuration
+pub const PACKAGE_JSON_CONFIG_KEY: &str = "nextButler";
+
+/// Files, inside the tool's directory, that can hold the configuration, in
+/// the order they are searched
+const CONFIG_FILES: [(&str, ConfigFormat); 5] = [
+    ("nextbutler.json", ConfigFormat::Json),
+    ("nextbutler.jsonc", ConfigFormat::Jsonc),
+    ("nextbutler.toml", ConfigFormat::Toml),
+    ("nextbutler.yaml", ConfigFormat::Yaml),
+    ("nextbutler.yml", ConfigFormat::Yaml),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigFormat {
+    Json,
+    /// JSON with comments and trailing commas
+    Jsonc,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Format of a configuration file, by its extension
+    pub fn from_path(config_path: &Path) -> Option<Self> {
+        match config_path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "jsonc" => Some(Self::Jsonc),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Where the configuration of a project is read from
+#[derive(Debug, PartialEq)]
+pub enum ConfigSource {
+    /// A configuration file, like the ones inside the tool's directory
+    File(PathBuf, ConfigFormat),
+    /// The "nextButler" key of the package.json
+    PackageJson(PathBuf),
+}
+
+impl ConfigSource {
+    /// Finds the configuration of the project in the given directory. Only
+    /// one source can be present, so a setting is never defined in two places
+    pub fn find(project_dir: &Path) -> Result<Option<Self>, String> {
+        let mut sources: Vec<Self> = CONFIG_FILES
+            .iter()
+            .map(|(file_name, format)| {
+                (
+                    project_dir.join(NEXT_BUTLER_DIR).join(file_name),
+                    *format,
+                )
+            })
+            .filter(|(config_path, _)| config_path.is_file())
+            .map(|(config_path, format)| Self::File(config_path, format))
+            .collect();
+
+        let package_json_path = project_dir.join("package.json");
+        if read_package_json_config(&package_json_path)?.is_some() {
+            sources.push(Self::PackageJson(package_json_path));
+        }
+
+        if sources.len() > 1 {
+            return Err(format!(
+                "Found several configurations: {}. Keep only one of them",
+                sources
+                    .iter()
+                    .map(Self::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+
+        Ok(sources.pop())
+    }
+
+    /// Reads the configuration as a JSON value
+    pub fn read(&self) -> Result<Value, String> {
+        let (config_path, format) = match self {
+            Self::File(config_path, format) => (config_path, format),
+            Self::PackageJson(package_json_path) => {
+                return read_package_json_config(package_json_path)
+                    .map(Option::unwrap_or_default);
+            }
+        };
+
+        let content = fs::read_to_string(config_path)
+            .map_err(|err| format!("Error reading {}: {}", config_path.display(), err))?;
+        let config = match format {
+            ConfigFormat::Json => serde_json::from_str(&content).map_err(|err| err.to_string()),
+            ConfigFormat::Jsonc => json5::from_str(&content).map_err(|err| err.to_string()),
+            ConfigFormat::Toml => toml::from_str(&content).map_err(|err| err.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(&content).map_err(|err| err.to_string()),
+        };
+
+        config.map_err(|err| format!("Invalid {}: {}", config_path.display(), err))
+    }
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(config_path, _) => write!(f, "{}", config_path.display()),
+            Self::PackageJson(package_json_path) => write!(
+                f,
+                "the \"{}\" key of {}",
+                PACKAGE_JSON_CONFIG_KEY,
+                package_json_path.display()
+            ),
+        }
+    }
+}
+
+/// Gets the "nextButler" key of the package.json, if the file and the key
+/// exist
+fn read_package_json_config(package_json_path: &Path) -> Result<Option<Value>, String> {
+    if !package_json_path.is_file() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(package_json_path)
+        .map_err(|err| format!("Error reading {}: {}", package_json_path.display(), err))?;
+    let mut package_json: Value = serde_json::from_str(&content)
+        .map_err(|err| format!("Invalid {}: {}", package_json_path.display(), err))?;
+
+    Ok(package_json
+        .get_mut(PACKAGE_JSON_CONFIG_KEY)
+        .map(Value::take))
+}
@@ -0,0 +1,278 @@
+use std::fs;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{
+    config_layers::{ConfigLayer, ConfigLayerKind, PRESETS_KEY},
+    config_schema::get_settings_tree,
+    config_source::{ConfigSource, PACKAGE_JSON_CONFIG_KEY},
+    UserConfig, WorkspaceConfig,
+};
+
+/// Key of the section listing the apps and packages of a workspace
+const WORKSPACE_KEY: &str = "workspace";
+
+/// Problem found in one of the configurations
+struct ConfigProblem {
+    /// Keys leading to the wrong value, like ["new", "page", "jsx"]
+    key_path: Vec<String>,
+    kind: ConfigProblemKind,
+}
+
+enum ConfigProblemKind {
+    /// A key the configuration doesn't have, with the most similar known one
+    UnknownKey(Option<String>),
+    /// A value that can't be read, with the reason
+    InvalidValue(String),
+}
+
+impl ConfigProblem {
+    fn get_message(&self) -> String {
+        let key_path = self.key_path.join(".");
+        match &self.kind {
+            ConfigProblemKind::UnknownKey(Some(similar)) => {
+                format!("unknown key \"{}\", did you mean `{}`?", key_path, similar)
+            }
+            ConfigProblemKind::UnknownKey(None) => format!("unknown key \"{}\"", key_path),
+            ConfigProblemKind::InvalidValue(reason) if key_path.is_empty() => reason.to_owned(),
+            ConfigProblemKind::InvalidValue(reason) => format!("\"{}\": {}", key_path, reason),
+        }
+    }
+}
+
+/// Checks every layer only has known keys and values of the expected type.
+/// The error lists each problem, with the file, line and column where it's
+/// found
+pub fn validate_config_layers(layers: &[ConfigLayer]) -> Result<(), String> {
+    let mut problems = vec![];
+
+    for layer in layers {
+        // The built-in configuration is always valid, and the presets are
+        // validated inside the layer defining them
+        if matches!(layer.kind, ConfigLayerKind::Default | ConfigLayerKind::Preset) {
+            continue;
+        }
+
+        let mut configs = vec![(vec![], &layer.config)];
+        if let Some(presets) = layer.config.get(PRESETS_KEY).and_then(Value::as_object) {
+            configs.extend(presets.iter().map(|(preset_name, preset)| {
+                (vec![PRESETS_KEY.to_owned(), preset_name.to_owned()], preset)
+            }));
+        }
+
+        for (key_prefix, config) in configs {
+            let Err(config_problems) =
+                validate_config::<UserConfig>(config, &key_prefix, get_settings_tree())
+            else {
+                continue;
+            };
+            for problem in config_problems {
+                let location = match &layer.config_source {
+                    Some(config_source) => get_location(config_source, &problem.key_path),
+                    None => layer.to_string(),
+                };
+                problems.push(format!("  {}: {}", location, problem.get_message()));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(format!("Invalid configuration:\n{}", problems.join("\n")))
+}
+
+/// Reads the `workspace` section of a configuration, checking it the same
+/// way as the rest of the configuration (see [validate_config_layers])
+pub fn read_workspace_config(
+    workspace: &Value,
+    config_source: &ConfigSource,
+) -> Result<WorkspaceConfig, String> {
+    let key_prefix = [WORKSPACE_KEY.to_owned()];
+    let known_config = get_settings_tree()
+        .get(WORKSPACE_KEY)
+        .unwrap_or(&Value::Null);
+    validate_config(workspace, &key_prefix, known_config).map_err(|problems| {
+        let problems: Vec<String> = problems
+            .iter()
+            .map(|problem| {
+                format!(
+                    "  {}: {}",
+                    get_location(config_source, &problem.key_path),
+                    problem.get_message()
+                )
+            })
+            .collect();
+        format!("Invalid configuration:\n{}", problems.join("\n"))
+    })
+}
+
+/// Reads the configuration as `T`, failing with its unknown keys and its
+/// values of the wrong type. Reading the configuration stops at the first wrong value,
+/// so it's read again without it until no wrong value is left. The keys of
+/// the problems start with `key_prefix`, the path of the configuration
+/// inside its file
+fn validate_config<T: DeserializeOwned>(
+    config: &Value,
+    key_prefix: &[String],
+    known_config: &Value,
+) -> Result<T, Vec<ConfigProblem>> {
+    let mut config = config.to_owned();
+    let mut invalid_values = vec![];
+
+    loop {
+        let mut unknown_keys = vec![];
+        let result: Result<T, _> =
+            serde_path_to_error::deserialize(serde_ignored::Deserializer::new(
+                &config,
+                &mut |path: serde_ignored::Path| {
+                    let mut key_path = vec![];
+                    collect_keys(&path, &mut key_path);
+                    unknown_keys.push(unknown_key(key_prefix, key_path, known_config));
+                },
+            ));
+
+        let err = match result {
+            Ok(config) if unknown_keys.is_empty() && invalid_values.is_empty() => return Ok(config),
+            Ok(_) => return Err(unknown_keys.into_iter().chain(invalid_values).collect()),
+            Err(err) => err,
+        };
+        let key_path: Vec<String> = err
+            .path()
+            .iter()
+            .map(|segment| match segment {
+                serde_path_to_error::Segment::Seq { index } => index.to_string(),
+                serde_path_to_error::Segment::Map { key } => key.to_owned(),
+                serde_path_to_error::Segment::Enum { variant } => variant.to_owned(),
+                serde_path_to_error::Segment::Unknown => String::from("?"),
+            })
+            .collect();
+        let is_removed = remove_value(&mut config, &key_path);
+        invalid_values.push(ConfigProblem {
+            key_path: [key_prefix, &key_path].concat(),
+            kind: ConfigProblemKind::InvalidValue(err.inner().to_string()),
+        });
+
+        if !is_removed {
+            return Err(unknown_keys.into_iter().chain(invalid_values).collect());
+        }
+    }
+}
+
+/// Removes the value at the path, or the list holding it, so the rest of the
+/// configuration can be checked. False if there is no such value
+fn remove_value(config: &mut Value, key_path: &[String]) -> bool {
+    let Some((key, parent_path)) = key_path.split_last() else {
+        return false;
+    };
+
+    match parent_path
+        .iter()
+        .try_fold(&mut *config, |value, key| value.get_mut(key))
+    {
+        Some(Value::Object(parent)) => parent.remove(key).is_some(),
+        // Removing an item would change the index of the next ones
+        Some(Value::Array(_)) => remove_value(config, parent_path),
+        _ => false,
+    }
+}
+
+fn collect_keys(path: &serde_ignored::Path, key_path: &mut Vec<String>) {
+    match path {
+        serde_ignored::Path::Root => {}
+        serde_ignored::Path::Seq { parent, index } => {
+            collect_keys(parent, key_path);
+            key_path.push(index.to_string());
+        }
+        serde_ignored::Path::Map { parent, key } => {
+            collect_keys(parent, key_path);
+            key_path.push(key.to_owned());
+        }
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => collect_keys(parent, key_path),
+    }
+}
+
+/// Problem of a key the configuration doesn't have, suggesting the most
+/// similar of the keys next to it
+fn unknown_key(
+    key_prefix: &[String],
+    key_path: Vec<String>,
+    known_config: &Value,
+) -> ConfigProblem {
+    let similar = key_path.split_last().and_then(|(key, parent_path)| {
+        parent_path
+            .iter()
+            .try_fold(known_config, |value, key| value.get(key))
+            .and_then(Value::as_object)?
+            .keys()
+            .map(|known_key| (known_key, strsim::jaro_winkler(key, known_key)))
+            .filter(|(_, similarity)| *similarity >= 0.8)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(known_key, _)| known_key.to_owned())
+    });
+
+    ConfigProblem {
+        key_path: [key_prefix, &key_path].concat(),
+        kind: ConfigProblemKind::UnknownKey(similar),
+    }
+}
+
+/// Where the key is defined: the file, line and column
+fn get_location(config_source: &ConfigSource, key_path: &[String]) -> String {
+    let (file_path, key_path) = match config_source {
+        ConfigSource::File(config_path, _) => (config_path, key_path.to_vec()),
+        ConfigSource::PackageJson(package_json_path) => (
+            package_json_path,
+            [vec![PACKAGE_JSON_CONFIG_KEY.to_owned()], key_path.to_vec()].concat(),
+        ),
+    };
+
+    let position = fs::read_to_string(file_path)
+        .ok()
+        .and_then(|content| find_key_position(&content, &key_path));
+    match position {
+        Some((line, column)) => format!("{}:{}:{}", file_path.display(), line, column),
+        None => file_path.display().to_string(),
+    }
+}
+
+/// Line and column, starting at 1, of the last key of the path. Each key is
+/// searched after the previous one, which works for JSON, YAML and TOML
+/// without parsing them again
+fn find_key_position(content: &str, key_path: &[String]) -> Option<(usize, usize)> {
+    let mut key_start = 0;
+    let mut search_start = 0;
+    for key in key_path {
+        key_start = search_start + find_key(&content[search_start..], key)?;
+        search_start = key_start + key.len();
+    }
+
+    let before_key = &content[..key_start];
+    let line = before_key.matches('\n').count() + 1;
+    let column = before_key
+        .rsplit('\n')
+        .next()
+        .map(|line_start| line_start.chars().count() + 1)
+        .unwrap_or(1);
+    Some((line, column))
+}
+
+/// Offset of the first use of the text as a key: a whole word followed by a
+/// separator, like `"key":`, `key =` or the `key.` and `key]` of a TOML
+/// table
+fn find_key(content: &str, key: &str) -> Option<usize> {
+    let is_word_char = |char: char| char.is_alphanumeric() || char == '_' || char == '-';
+
+    content.match_indices(key).map(|(offset, _)| offset).find(|offset| {
+        let preceded_by_word = content[..*offset].chars().next_back().is_some_and(is_word_char);
+        let rest = content[offset + key.len()..].trim_start_matches(['"', '\'']);
+        let followed_by_separator = rest
+            .trim_start_matches([' ', '\t'])
+            .starts_with([':', '=', '.', ']']);
+
+        !preceded_by_word && !rest.starts_with(is_word_char) && followed_by_separator
+    })
+}
@@ -1,25 +1,49 @@
-use std::path::PathBuf;
+pub mod config_layers;
+mod config_schema;
+pub mod config_source;
+mod config_validation;
 
+use std::{collections::BTreeMap, path::Path};
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    constants::{CONFIG_FILE_NAME, NEXT_BUTLER_DIR},
-    helpers::file_helper::json_file_to_struct,
+    constants::CONFIG_SCHEMA_FILE_NAME,
     react_extension::{GuessReactExtension, ReactExtension},
 };
 
-#[derive(Deserialize, Serialize, Debug)]
+pub use self::config_validation::read_workspace_config;
+
+use self::config_layers::{get_config_layers, merge_config_layers, ConfigLayer, ConfigLayerKind};
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[schemars(deny_unknown_fields)]
 pub struct UserNewPageConfig {
     /// Create files as typescript
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub typescript: Option<bool>,
     /// Create files as .jsx (or .tsx if typescript is true)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub jsx: Option<bool>,
     /// Which custom template to use by default
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub template: Option<String>,
     /// Which custom template to use by default
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub api_template: Option<String>,
     /// Create page based on the old page router
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page_router: Option<bool>,
+    /// Variables passed to the page templates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<BTreeMap<String, String>>,
+    /// Pattern of the new pages' path, relative to the project's root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Pattern of the new API pages' path, relative to the project's root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_path: Option<String>,
 }
 
 impl UserNewPageConfig {
@@ -30,6 +54,9 @@ impl UserNewPageConfig {
             template: None,
             api_template: None,
             page_router: Some(false),
+            variables: None,
+            path: None,
+            api_path: None,
         }
     }
 }
@@ -51,16 +78,27 @@ impl GuessReactExtension for UserNewPageConfig {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[schemars(deny_unknown_fields)]
 pub struct UserNewComponentConfig {
     /// Create files as typescript
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub typescript: Option<bool>,
     /// Create files as .jsx (or .tsx if typescript is true)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub jsx: Option<bool>,
     /// Where to save the new components
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub folder: Option<String>,
     /// Which custom template to use by default
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub template: Option<String>,
+    /// Variables passed to the component templates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<BTreeMap<String, String>>,
+    /// Pattern of the new components' path, relative to the project's root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
 impl UserNewComponentConfig {
@@ -70,6 +108,8 @@ impl UserNewComponentConfig {
             jsx: Some(true),
             folder: Some(String::from("components")),
             template: None,
+            variables: None,
+            path: None,
         }
     }
 }
@@ -91,14 +131,24 @@ impl GuessReactExtension for UserNewComponentConfig {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[schemars(deny_unknown_fields)]
 pub struct UserNewStyleConfig {
     /// Which extension to use
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub extension: Option<String>,
     /// Which custom template to use by default
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub template: Option<String>,
     /// Where the stylesheets should be created
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub folder: Option<String>,
+    /// Variables passed to the stylesheet templates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<BTreeMap<String, String>>,
+    /// Pattern of the new stylesheets' path, relative to the project's root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
 impl UserNewStyleConfig {
@@ -107,37 +157,144 @@ impl UserNewStyleConfig {
             extension: Some(String::from("css")),
             folder: Some(String::from("styles")),
             template: None,
+            variables: None,
+            path: None,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[schemars(deny_unknown_fields)]
 pub struct New {
+    /// Defaults of `nb new page`
+    #[serde(skip_serializing_if = "Option::is_none")]
     page: Option<UserNewPageConfig>,
+    /// Defaults of `nb new style`
+    #[serde(skip_serializing_if = "Option::is_none")]
     style: Option<UserNewStyleConfig>,
+    /// Defaults of `nb new component`
+    #[serde(skip_serializing_if = "Option::is_none")]
     component: Option<UserNewComponentConfig>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Apps and packages of a workspace (like a Turborepo), set in the
+/// configuration file at its root. Its other settings are the defaults of
+/// every app and package, which can override them with their own
+/// configuration file
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[schemars(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// Folder of every Next.js app, relative to the workspace's root, by name
+    pub apps: BTreeMap<String, String>,
+    /// Folder of every shared package, relative to the workspace's root, by
+    /// name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packages: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[schemars(title = "next-butler configuration", deny_unknown_fields)]
 /// The configuration provided by the user.
 ///
 /// Can be either defined in the configuration file or via
 /// the tool options.
 pub struct UserConfig {
+    /// Schema of the configuration, for the editors
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Defaults of the `new` command
+    #[serde(skip_serializing_if = "Option::is_none")]
     new: Option<New>,
+    /// Fail when a template uses an undefined variable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict_templates: Option<bool>,
+    /// Extra directories where to search templates, after the project's and
+    /// the user's ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_dirs: Option<Vec<String>>,
+    /// Template packs (directories or .tar.gz archives) installed by
+    /// `nb template install`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_packs: Option<Vec<String>>,
+    /// Extensions to prefer, in order, when a template name matches several
+    /// variants (like "card.tsx.hbs" and "card.jsx.hbs") and nothing else
+    /// decides the extension of the file to create
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_extension_order: Option<Vec<String>>,
+    /// Folder holding the routers and the components, relative to the
+    /// project's root. By default, "src/" if it exists and there is no app/
+    /// or pages/ folder at the root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub src_dir: Option<String>,
+    /// Folder of the app router, relative to the project's root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_dir: Option<String>,
+    /// Folder of the pages router, relative to the project's root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages_dir: Option<String>,
+    /// Folder of the static files, relative to the project's root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_dir: Option<String>,
+    /// Folder where components are created, relative to the project's root.
+    /// Takes precedence over the `folder` of the component's configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components_dir: Option<String>,
+    /// Apps and packages, when this is the configuration of a workspace
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<WorkspaceConfig>,
+    /// Path of a configuration this one is based on, overriding its values.
+    /// Names that are not paths are searched inside node_modules/
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// Named configurations overlaying this one when chosen with --preset,
+    /// like `{ "admin": { "new": { ... } } }`
+    #[schemars(with = "Option<BTreeMap<String, UserConfig>>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presets: Option<BTreeMap<String, serde_json::Value>>,
+    /// Preset applied when none is chosen with --preset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_preset: Option<String>,
 }
 
 impl UserConfig {
-    /// Returns the user-defined configuration or the default if it doesn't exist
+    /// Returns the user-defined configuration or the default if it doesn't exist.
+    /// It's read from the first of nextbutler/nextbutler.json, .jsonc, .toml,
+    /// .yaml or the "nextButler" key of the package.json, which must be the
+    /// only one present.
+    ///
+    /// The project's configuration is merged over the workspace's one and the
+    /// user-level one, then the selected preset and the NB_* environment
+    /// variables override them (see `get_config_layers`)
     pub fn get() -> Result<Self, String> {
-        let config_file = PathBuf::from(format!("{}{}", NEXT_BUTLER_DIR, CONFIG_FILE_NAME));
+        Self::from_layers(&get_config_layers(None)?)
+    }
 
-        if config_file.exists() {
-            json_file_to_struct(&config_file)
-                .map_err(|err| format!("Custom configuration error: {}", err))
-        } else {
-            Ok(Self::get_default())
+    /// Returns the configuration of the files created inside the given
+    /// folder, overridden by the .nextbutler.json of the folders holding it.
+    /// `None` if none of them has one, so the result of `get` applies
+    pub fn get_for_dir(target_dir: &Path) -> Result<Option<Self>, String> {
+        let layers = get_config_layers(Some(target_dir))?;
+        if !layers
+            .iter()
+            .any(|layer| layer.kind == ConfigLayerKind::Directory)
+        {
+            return Ok(None);
         }
+
+        Self::from_layers(&layers).map(Some)
+    }
+
+    fn from_layers(layers: &[ConfigLayer]) -> Result<Self, String> {
+        let (config, _) = merge_config_layers(layers);
+        serde_json::from_value(config).map_err(|err| format!("Custom configuration error: {}", err))
+    }
+
+    /// Folder where components are created, relative to the source folder
+    pub fn get_component_folder(&self) -> Option<&str> {
+        self.new
+            .as_ref()
+            .and_then(|new_cmd_cfg| new_cmd_cfg.component.as_ref())
+            .and_then(|comp_cfg| comp_cfg.folder.as_deref())
     }
 
     pub fn get_new_cmd_config(self) -> Option<New> {
@@ -148,7 +305,7 @@ impl UserConfig {
         if let Some(new_cmd_cfg) = self.new {
             new_cmd_cfg
                 .get_page_config()
-                .map_or_else(UserNewPageConfig::get_default, |v| v)
+                .unwrap_or_else(UserNewPageConfig::get_default)
         } else {
             UserNewPageConfig::get_default()
         }
@@ -158,7 +315,7 @@ impl UserConfig {
         if let Some(new_cmd_cfg) = self.new {
             new_cmd_cfg
                 .get_component_config()
-                .map_or_else(UserNewComponentConfig::get_default, |v| v)
+                .unwrap_or_else(UserNewComponentConfig::get_default)
         } else {
             UserNewComponentConfig::get_default()
         }
@@ -168,7 +325,7 @@ impl UserConfig {
         if let Some(new_cmd_cfg) = self.new {
             new_cmd_cfg
                 .get_style_config()
-                .map_or_else(UserNewStyleConfig::get_default, |v| v)
+                .unwrap_or_else(UserNewStyleConfig::get_default)
         } else {
             UserNewStyleConfig::get_default()
         }
@@ -176,18 +333,45 @@ impl UserConfig {
 
     pub fn get_default() -> Self {
         Self {
+            schema: None,
             new: Some(New {
                 page: Some(UserNewPageConfig::get_default()),
                 style: Some(UserNewStyleConfig::get_default()),
                 component: Some(UserNewComponentConfig::get_default()),
             }),
+            strict_templates: Some(false),
+            template_dirs: None,
+            template_packs: None,
+            template_extension_order: None,
+            src_dir: None,
+            app_dir: None,
+            pages_dir: None,
+            public_dir: None,
+            components_dir: None,
+            workspace: None,
+            extends: None,
+            presets: None,
+            default_preset: None,
         }
     }
 
+    /// The default configuration file, referring to the schema created next to
+    /// it by `nb init`
     pub fn get_default_as_vec() -> Result<Vec<u8>, String> {
-        serde_json::to_vec_pretty(&Self::get_default())
+        let default_config = Self {
+            schema: Some(format!("./{}", CONFIG_SCHEMA_FILE_NAME)),
+            ..Self::get_default()
+        };
+        serde_json::to_vec_pretty(&default_config)
             .map_err(|err| format!("Error building the default configuration file: {}", err))
     }
+
+    /// JSON Schema of the configuration files, generated from these types so
+    /// it always matches them. The doc comments are the descriptions
+    pub fn get_json_schema() -> Result<Vec<u8>, String> {
+        serde_json::to_vec_pretty(&schemars::schema_for!(Self))
+            .map_err(|err| format!("Error building the configuration's schema: {}", err))
+    }
 }
 
 impl New {
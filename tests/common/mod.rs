@@ -0,0 +1,38 @@
+#![allow(dead_code)]
+
+use std::{fs, path::Path};
+
+use assert_cmd::Command;
+use tempfile::TempDir;
+
+/// Creates an empty Next.js-like project, with both the app and the page
+/// routers folders inside src/
+pub fn new_project() -> TempDir {
+    let project = tempfile::tempdir().unwrap();
+    fs::create_dir_all(project.path().join("src/app")).unwrap();
+    fs::create_dir_all(project.path().join("src/pages")).unwrap();
+    fs::create_dir_all(project.path().join("src/components")).unwrap();
+    fs::create_dir_all(project.path().join("src/styles")).unwrap();
+    project
+}
+
+/// Writes a file inside the project, creating its parent folders
+pub fn write_file(project: &TempDir, path: impl AsRef<Path>, content: &str) {
+    let path = project.path().join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+pub fn read_file(project: &TempDir, path: impl AsRef<Path>) -> String {
+    fs::read_to_string(project.path().join(path)).unwrap()
+}
+
+/// The tool's binary, running inside the given project. The user's
+/// configuration directory points inside the project, so the templates and
+/// configuration of whoever runs the tests are not used
+pub fn nb(project: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("nb").unwrap();
+    cmd.current_dir(project.path());
+    cmd.env("XDG_CONFIG_HOME", project.path().join(".config"));
+    cmd
+}
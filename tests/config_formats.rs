@@ -0,0 +1,83 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+/**
+ *  The configuration can be written in JSON, JSONC, TOML, YAML or inside the
+ *  package.json.
+ * */
+
+#[test]
+fn test_jsonc_config() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.jsonc",
+        r#"{
+            // Every component is written in TypeScript
+            "new": { "component": { "typescript": true, "jsx": true, }, },
+        }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/card.tsx").is_file());
+}
+
+#[test]
+fn test_toml_and_yaml_config() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.toml",
+        "# Team conventions\n[new.component]\ntypescript = true\njsx = false\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/card.ts").is_file());
+
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.yaml",
+        "new:\n  style:\n    extension: scss\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "style", "card", "--folder", "styles"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/styles/card/card.scss").is_file());
+}
+
+#[test]
+fn test_package_json_config() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "package.json",
+        r#"{ "name": "web", "nextButler": { "new": { "component": { "jsx": false } } } }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/card.js").is_file());
+}
+
+#[test]
+fn test_several_configs() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/nextbutler.json", "{}");
+    common::write_file(&project, "package.json", r#"{ "nextButler": {} }"#);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().failure().stderr(
+        contains("Found several configurations")
+            .and(contains("nextbutler.json"))
+            .and(contains("the \"nextButler\" key of")),
+    );
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+/**
+ *  The configuration merges the user-level one, the workspace's one and the
+ *  project's one, each after the configurations it extends.
+ * */
+
+#[test]
+fn test_user_config_and_extends() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        ".config/next-butler/config.json",
+        r#"{ "strict_templates": true, "new": { "component": { "typescript": true, "jsx": true } } }"#,
+    );
+    common::write_file(
+        &project,
+        "node_modules/@acme/nb-config/nextbutler.json",
+        r#"{ "new": { "style": { "extension": "scss" }, "component": { "jsx": true } } }"#,
+    );
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "extends": "@acme/nb-config", "new": { "component": { "jsx": false } } }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/card.ts").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["config", "show", "--resolved", "--origin"]);
+    cmd.assert().success().stdout(
+        contains("new.component.jsx = false  # project (nextbutler/nextbutler.json)")
+            .and(contains("new.component.typescript = true  # user ("))
+            .and(contains(
+                "new.style.extension = \"scss\"  # extended (node_modules/@acme/nb-config/nextbutler.json)",
+            ))
+            .and(contains("strict_templates = true  # user (")),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["config", "show"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("\"jsx\": false").and(contains("strict_templates").not()));
+}
+
+#[test]
+fn test_extends_cycle() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/base.json", r#"{ "extends": "./nextbutler.json" }"#);
+    common::write_file(&project, "nextbutler/nextbutler.json", r#"{ "extends": "./base.json" }"#);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().failure().stderr(contains("extends itself"));
+
+    common::write_file(&project, "nextbutler/nextbutler.json", r#"{ "extends": "./missing.json" }"#);
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Couldn't find the configuration './missing.json'"));
+}
+
+#[test]
+fn test_env_overrides() {
+    let project = common::new_project();
+    std::fs::create_dir_all(project.path().join("src/ui")).unwrap();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": { "typescript": false, "jsx": true } } }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.env("NB_NEW_COMPONENT_TYPESCRIPT", "true")
+        .env("NB_NEW_COMPONENT_FOLDER", "ui")
+        .env("NB_NEW_COMPONENT_VARIABLES_OWNER", "ACME")
+        .env("NB_NEW_PAGE_API_PATH", "{{folder}}/{{path}}/route.{{ext}}");
+    cmd.args(["config", "show", "--resolved", "--origin"]);
+    cmd.assert().success().stdout(
+        contains("new.component.typescript = true  # environment (NB_NEW_COMPONENT_TYPESCRIPT)")
+            .and(contains("new.component.folder = \"ui\""))
+            .and(contains("new.component.variables.owner = \"ACME\""))
+            .and(contains("new.page.api_path = \"{{folder}}/{{path}}/route.{{ext}}\"")),
+    );
+
+    // Flags take precedence over the environment variables
+    let mut cmd = common::nb(&project);
+    cmd.env("NB_NEW_COMPONENT_TYPESCRIPT", "true")
+        .env("NB_NEW_COMPONENT_FOLDER", "ui");
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/ui/card.tsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.env("NB_NEW_COMPONENT_TYPESCRIPT", "true");
+    cmd.args(["new", "component", "other", "--jsx"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/other.jsx").is_file());
+}
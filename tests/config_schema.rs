@@ -0,0 +1,52 @@
+mod common;
+
+use predicates::str::contains;
+use serde_json::Value;
+
+/**
+ *  The JSON Schema of the configuration is generated from its types, and
+ *  `nb init` refers to it from the configuration it creates.
+ * */
+#[test]
+fn test_config_schema() {
+    let project = common::new_project();
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["config", "schema"]);
+    let output = cmd.assert().success().get_output().stdout.to_owned();
+    let schema: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(schema["additionalProperties"], Value::Bool(false));
+    assert_eq!(
+        schema["properties"]["strict_templates"]["description"],
+        "Fail when a template uses an undefined variable"
+    );
+    assert_eq!(
+        schema["$defs"]["UserNewPageConfig"]["properties"]["page_router"]["description"],
+        "Create page based on the old page router"
+    );
+}
+
+#[test]
+fn test_init_refers_to_schema() {
+    let project = common::new_project();
+
+    let mut cmd = common::nb(&project);
+    cmd.arg("init");
+    cmd.assert().success();
+
+    let config: Value =
+        serde_json::from_str(&common::read_file(&project, "nextbutler/nextbutler.json")).unwrap();
+    assert_eq!(config["$schema"], "./nextbutler.schema.json");
+    assert!(!common::read_file(&project, "nextbutler/nextbutler.json").contains("null"));
+    let schema: Value =
+        serde_json::from_str(&common::read_file(&project, "nextbutler/nextbutler.schema.json"))
+            .unwrap();
+    assert_eq!(schema["title"], "next-butler configuration");
+
+    // The "$schema" key is a valid setting
+    let mut cmd = common::nb(&project);
+    cmd.args(["config", "show"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("\"$schema\": \"./nextbutler.schema.json\""));
+}
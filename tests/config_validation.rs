@@ -0,0 +1,117 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+/**
+ *  Unknown keys and values of the wrong type are reported with the file, line
+ *  and column where they are, instead of being ignored.
+ * */
+#[test]
+fn test_unknown_keys() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{
+    "new": {
+        "page": { "typscript": true },
+        "compnent": { "jsx": true }
+    },
+    "presets": { "admin": { "strict_template": true } }
+}"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "about"]);
+    cmd.assert().failure().stderr(
+        contains("Invalid configuration:")
+            .and(contains(
+                "nextbutler/nextbutler.json:3:20: unknown key \"new.page.typscript\", did you mean `typescript`?",
+            ))
+            .and(contains(
+                "nextbutler/nextbutler.json:4:10: unknown key \"new.compnent\", did you mean `component`?",
+            ))
+            .and(contains(
+                "nextbutler/nextbutler.json:6:30: unknown key \"presets.admin.strict_template\", did you mean `strict_templates`?",
+            )),
+    );
+    assert!(!project.path().join("src/app/about/page.jsx").exists());
+}
+
+#[test]
+fn test_invalid_types() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.toml",
+        "[new.component]\njsx = \"yes\"\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().failure().stderr(contains(
+        "nextbutler/nextbutler.toml:2:1: \"new.component.jsx\": invalid type: string \"yes\", expected a boolean",
+    ));
+
+    common::write_file(&project, "nextbutler/nextbutler.toml", "");
+    let mut cmd = common::nb(&project);
+    cmd.env("NB_NEW_COMPONENT_TYPESCRIPT", "sure");
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().failure().stderr(contains(
+        "environment (NB_NEW_COMPONENT_TYPESCRIPT): \"new.component.typescript\": invalid type: string \"sure\", expected a boolean",
+    ));
+}
+
+#[test]
+fn test_every_problem_is_reported() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{
+    "new": {
+        "page": { "typscript": true, "jsx": "yes" },
+        "component": { "folder": 3 }
+    }
+}"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "about"]);
+    cmd.assert().failure().stderr(
+        contains(
+            "nextbutler/nextbutler.json:3:20: unknown key \"new.page.typscript\", did you mean `typescript`?",
+        )
+        .and(contains(
+            "nextbutler/nextbutler.json:3:39: \"new.page.jsx\": invalid type: string \"yes\", expected a boolean",
+        ))
+        .and(contains(
+            "nextbutler/nextbutler.json:4:25: \"new.component.folder\": invalid type: integer `3`, expected a string",
+        )),
+    );
+}
+
+#[test]
+fn test_invalid_workspace() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{
+    "workspace": {
+        "aps": { "web": "apps/web" },
+        "packages": []
+    }
+}"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().failure().stderr(
+        contains("nextbutler/nextbutler.json:3:10: unknown key \"workspace.aps\", did you mean `apps`?")
+            .and(contains(
+                "nextbutler/nextbutler.json:4:10: \"workspace.packages\": invalid type: sequence, expected a map",
+            ))
+            .and(contains("nextbutler/nextbutler.json:2:6: \"workspace\": missing field `apps`")),
+    );
+}
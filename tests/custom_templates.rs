@@ -0,0 +1,388 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+#[test]
+fn test_shared_partials() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/partials/header.hbs",
+        "// (c) {{owner}}\n",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/partials/imports/react.hbs",
+        "import React from \"react\";\n",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.hbs",
+        "{{> header}}{{> imports/react}}export default function {{name}}() {}",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/pages/home.hbs",
+        "{{> header}}export default function {{name}}Page() {}",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--jsx", "--template", "card"]);
+    cmd.args(["--var", "owner=ACME"]);
+    cmd.assert().success();
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "/home", "--jsx", "--template", "home"]);
+    cmd.args(["--var", "owner=ACME"]);
+    cmd.assert().success();
+
+    assert_eq!(
+        common::read_file(&project, "src/components/card.jsx"),
+        "// (c) ACME\nimport React from \"react\";\nexport default function Card() {}"
+    );
+    assert_eq!(
+        common::read_file(&project, "src/app/home/page.jsx"),
+        "// (c) ACME\nexport default function HomePage() {}"
+    );
+}
+
+#[test]
+fn test_missing_partial() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.hbs",
+        "{{> license}}",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--jsx", "--template", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Partial 'license'").and(contains("card.hbs")));
+    assert!(!project.path().join("src/components/card.jsx").exists());
+}
+
+#[test]
+fn test_yaml_front_matter() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.hbs",
+        indoc::indoc! {r#"
+            ---
+            extension: tsx
+            path: "{{folder}}/{{kebab name}}/index.{{ext}}"
+            variables:
+              owner:
+                description: Copyright owner
+                default: ACME
+            ---
+            // (c) {{owner}}
+            export default function {{name}}() {}
+        "#},
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "UserCard", "--template", "card"]);
+    cmd.assert().success();
+
+    assert_eq!(
+        common::read_file(&project, "src/components/user-card/index.tsx"),
+        "// (c) ACME\nexport default function UserCard() {}\n"
+    );
+}
+
+#[test]
+fn test_front_matter_scalar_defaults() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/yaml.hbs",
+        "---\nextension: jsx\nvariables:\n  size:\n    default: 3\n  shadow:\n    default: true\n---\n{{size}} {{shadow}}",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/toml.hbs",
+        "+++\nextension = \"jsx\"\n[variables.ratio]\ndefault = 1.5\n+++\n{{ratio}}",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/list.hbs",
+        "---\nextension: jsx\nvariables:\n  tags:\n    default: [a]\n---\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "yaml"]);
+    cmd.assert().success();
+    assert_eq!(common::read_file(&project, "src/components/card.jsx"), "3 true");
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "badge", "--template", "toml"]);
+    cmd.assert().success();
+    assert_eq!(common::read_file(&project, "src/components/badge.jsx"), "1.5");
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "other", "--template", "list"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("variables.tags: the default value must be a string, number or boolean"));
+}
+
+#[test]
+fn test_toml_front_matter_required_variable() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.hbs",
+        indoc::indoc! {r#"
+            +++
+            [variables.owner]
+            description = "Copyright owner"
+            required = true
+            +++
+            // (c) {{owner}}
+        "#},
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--jsx", "--template", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Missing required template variable 'owner' (Copyright owner)"));
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--jsx", "--template", "card"]);
+    cmd.args(["--var", "owner=ACME"]);
+    cmd.assert().success();
+    assert_eq!(
+        common::read_file(&project, "src/components/card.jsx"),
+        "// (c) ACME\n"
+    );
+}
+
+#[test]
+fn test_strict_templates() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/typo.hbs",
+        "---\nextension: jsx\n---\nexport default function {{nmae}}() {}\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "typo", "--strict"]);
+    cmd.assert().failure().stderr(
+        contains("Undefined variable 'nmae'")
+            .and(contains("typo.hbs:4:25"))
+            .and(contains("Available variables: name"))
+            .and(contains("did you mean `{{name}}`?")),
+    );
+    assert!(!project.path().join("src/components/card.jsx").exists());
+
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "strict_templates": true }"#,
+    );
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "typo"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Undefined variable 'nmae'"));
+}
+
+#[test]
+fn test_template_layers() {
+    let project = common::new_project();
+    let user_config_home = tempfile::tempdir().unwrap();
+    common::write_file(&project, "shared/components/card.jsx.hbs", "shared");
+    common::write_file(&project, "shared/components/badge.jsx.hbs", "shared");
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "template_dirs": ["shared"] }"#,
+    );
+    let user_template = user_config_home
+        .path()
+        .join("next-butler/templates/components/card.jsx.hbs");
+    std::fs::create_dir_all(user_template.parent().unwrap()).unwrap();
+    std::fs::write(&user_template, "user").unwrap();
+
+    let mut cmd = common::nb(&project);
+    cmd.env("XDG_CONFIG_HOME", user_config_home.path());
+    cmd.args(["new", "component", "card", "--template", "card", "--explain"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("resolved from the user templates"));
+    assert_eq!(common::read_file(&project, "src/components/card.jsx"), "user");
+
+    let mut cmd = common::nb(&project);
+    cmd.env("XDG_CONFIG_HOME", user_config_home.path());
+    cmd.args(["new", "component", "badge", "--template", "badge", "--explain"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("resolved from the extra directory templates"));
+    assert_eq!(common::read_file(&project, "src/components/badge.jsx"), "shared");
+
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.jsx.hbs",
+        "project",
+    );
+    let mut cmd = common::nb(&project);
+    cmd.env("XDG_CONFIG_HOME", user_config_home.path());
+    cmd.args(["new", "component", "other", "--template", "card", "--explain"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("resolved from the project templates"));
+    assert_eq!(common::read_file(&project, "src/components/other.jsx"), "project");
+}
+
+#[test]
+fn test_template_variant_by_extension() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/templates/components/card.jsx.hbs", "jsx");
+    common::write_file(&project, "nextbutler/templates/components/card.tsx.hbs", "tsx");
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--tsx", "--template", "card"]);
+    cmd.assert().success();
+    assert_eq!(common::read_file(&project, "src/components/card.tsx"), "tsx");
+
+    // Nothing decides the extension and the session is not interactive
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": {} } }"#,
+    );
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "other", "--template", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Found multiple templates named 'card'"));
+
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": {} }, "template_extension_order": ["tsx", "jsx"] }"#,
+    );
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "other", "--template", "card"]);
+    cmd.assert().success();
+    assert_eq!(common::read_file(&project, "src/components/other.tsx"), "tsx");
+}
+
+#[test]
+fn test_template_compile_error() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/broken.hbs",
+        "---\nextension: jsx\n---\n{{#if name}}\n  <div />\n{{/each}}\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "broken"]);
+    cmd.assert().failure().stderr(
+        contains("helper \"if\" was opened, but \"each\" is closing")
+            .and(contains("broken.hbs:6:1"))
+            .and(contains("6 | {{/each}}\n  | ^")),
+    );
+}
+
+#[test]
+fn test_jinja_template() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/partials/header.jinja",
+        "// {{ name | kebab }}\n",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.tsx.jinja",
+        "{% include \"header\" %}\
+export default function {{ pascal(name) }}() {
+{%- for tag in tags %}
+  // {{ tag | upper }}
+{%- endfor %}
+}
+",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args([
+        "new",
+        "component",
+        "invoice-row",
+        "--template",
+        "card",
+        "--var",
+        "tags=a",
+    ]);
+    cmd.assert().success();
+    assert_eq!(
+        common::read_file(&project, "src/components/invoice-row.tsx"),
+        "// invoice-row\nexport default function InvoiceRow() {\n  // A\n}\n"
+    );
+}
+
+#[test]
+fn test_jinja_strict_template() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/typo.jsx.j2",
+        "---\nextension: jsx\n---\nexport default function {{ nmae }}() {}\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "typo", "--strict"]);
+    cmd.assert().failure().stderr(
+        contains("Undefined variable 'nmae'")
+            .and(contains("typo.jsx.j2:4:28"))
+            .and(contains("did you mean `{{ name }}`?")),
+    );
+    assert!(!project.path().join("src/components/card.jsx").exists());
+}
+
+#[test]
+fn test_engines_render_variables_alike() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/hbs_card.tsx.hbs",
+        "import ui from '{{ui}}';\nexport const title = \"{{title}}\";\n",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/jinja_card.tsx.jinja",
+        "import ui from '{{ ui }}';\nexport const title = \"{{ title }}\";\n",
+    );
+
+    for (template, component) in [("hbs_card", "hbs-card"), ("jinja_card", "jinja-card")] {
+        let mut cmd = common::nb(&project);
+        cmd.args([
+            "new",
+            "component",
+            component,
+            "--template",
+            template,
+            "--var",
+            r#"title=Tom's "best" & <co>"#,
+            "--var",
+            "ui=@acme/ui?x=1",
+        ]);
+        cmd.assert().success();
+    }
+
+    let hbs_output = common::read_file(&project, "src/components/hbs-card.tsx");
+    assert_eq!(
+        hbs_output,
+        "import ui from '@acme/ui?x=1';\nexport const title = \"Tom's \"best\" & <co>\";\n"
+    );
+    assert_eq!(
+        common::read_file(&project, "src/components/jinja-card.tsx"),
+        hbs_output
+    );
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+/**
+ *  The built-in templates have a variant for every extension, so typescript
+ *  files get typed content.
+ * */
+
+#[test]
+fn test_typed_api_page() {
+    let project = common::new_project();
+
+    common::nb(&project)
+        .args(["new", "page", "/api/hello", "--page-router", "--ts"])
+        .assert()
+        .success();
+    let content = common::read_file(&project, "src/pages/api/hello.ts");
+    assert!(contains("NextApiResponse<Data>").eval(&content), "{}", content);
+}
+
+#[test]
+fn test_typed_page_and_component() {
+    let project = common::new_project();
+
+    common::nb(&project)
+        .args(["new", "page", "/users", "--tsx"])
+        .assert()
+        .success();
+    let content = common::read_file(&project, "src/app/users/page.tsx");
+    assert!(contains("export type UsersProps").eval(&content), "{}", content);
+    // Nothing unused that a default lint setup would report
+    assert!(contains("export default function Users() {").eval(&content), "{}", content);
+
+    common::nb(&project)
+        .args(["new", "page", "/orders", "--ts"])
+        .assert()
+        .success();
+    let content = common::read_file(&project, "src/app/orders/page.ts");
+    assert!(
+        contains("export default function Orders(): ReactElement {").eval(&content),
+        "{}",
+        content
+    );
+
+    common::nb(&project)
+        .args(["new", "component", "card", "--tsx"])
+        .assert()
+        .success();
+    let content = common::read_file(&project, "src/components/card.tsx");
+    assert!(contains("children?: ReactNode").eval(&content), "{}", content);
+}
+
+#[test]
+fn test_typed_page_router_page() {
+    let project = common::new_project();
+
+    common::nb(&project)
+        .args(["new", "page", "/users", "--page-router", "--tsx"])
+        .assert()
+        .success();
+    let content = common::read_file(&project, "src/pages/users.tsx");
+    assert!(contains("function Users(): ReactElement").eval(&content), "{}", content);
+    assert!(!contains("Params").eval(&content), "{}", content);
+}
+
+#[test]
+fn test_untyped_component() {
+    let project = common::new_project();
+
+    common::nb(&project)
+        .args(["new", "component", "card", "--jsx"])
+        .assert()
+        .success();
+    let content = common::read_file(&project, "src/components/card.jsx");
+    assert!(!contains("ReactNode").eval(&content), "{}", content);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+/**
+ *  A .nextbutler.json overrides the configuration for everything created
+ *  inside its folder. The nearest one wins.
+ * */
+#[test]
+fn test_dir_overrides() {
+    let project = common::new_project();
+    std::fs::create_dir_all(project.path().join("src/components/ui/forms")).unwrap();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": { "typescript": false, "jsx": true } } }"#,
+    );
+    common::write_file(
+        &project,
+        "src/components/ui/.nextbutler.json",
+        r#"{ "new": { "component": { "typescript": true } } }"#,
+    );
+    common::write_file(
+        &project,
+        "src/components/ui/forms/.nextbutler.json",
+        r#"{ "new": { "component": { "jsx": false } } }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/card.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "ui/button"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/ui/button.tsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "ui/forms/input"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/ui/forms/input.ts").is_file());
+
+    // Flags still take precedence
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "ui/link", "--jsx"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/ui/link.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["config", "show", "--resolved", "--origin", "--dir", "src/components/ui/forms"]);
+    cmd.assert().success().stdout(
+        contains("new.component.jsx = false  # directory (src/components/ui/forms/.nextbutler.json)")
+            .and(contains(
+                "new.component.typescript = true  # directory (src/components/ui/.nextbutler.json)",
+            )),
+    );
+}
+
+#[test]
+fn test_dir_override_template() {
+    let project = common::new_project();
+    std::fs::create_dir_all(project.path().join("src/app/(admin)")).unwrap();
+    common::write_file(
+        &project,
+        "nextbutler/templates/pages/admin.tsx.hbs",
+        "export default function {{name}}() { return <h1>Admin</h1> }",
+    );
+    common::write_file(
+        &project,
+        "src/app/(admin)/.nextbutler.json",
+        r#"{ "new": { "page": { "template": "admin" } } }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "(admin)/users"]);
+    cmd.assert().success();
+    assert!(common::read_file(&project, "src/app/(admin)/users/page.tsx").contains("Admin"));
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "about"]);
+    cmd.assert().success();
+    assert!(!common::read_file(&project, "src/app/about/page.jsx").contains("Admin"));
+}
+
+#[test]
+fn test_dir_override_template_extension_order() {
+    let project = common::new_project();
+    std::fs::create_dir_all(project.path().join("src/components/ui")).unwrap();
+    common::write_file(&project, "nextbutler/templates/components/card.jsx.hbs", "jsx");
+    common::write_file(&project, "nextbutler/templates/components/card.tsx.hbs", "tsx");
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": { "template": "card" } } }"#,
+    );
+    common::write_file(
+        &project,
+        "src/components/ui/.nextbutler.json",
+        r#"{ "template_extension_order": ["tsx", "jsx"] }"#,
+    );
+
+    // Only the folder's configuration decides which variant to use
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Found multiple templates named 'card'"));
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "ui/card"]);
+    cmd.assert().success();
+    assert_eq!(common::read_file(&project, "src/components/ui/card.tsx"), "tsx");
+}
@@ -0,0 +1,76 @@
+mod common;
+
+/**
+ *  Generated files follow the project's .editorconfig and Prettier
+ *  configuration.
+ * */
+
+#[test]
+fn test_prettier_config() {
+    let project = common::new_project();
+    common::write_file(&project, ".prettierrc", "useTabs: true\nsemi: false\nsingleQuote: true\n");
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--tsx"]);
+    cmd.assert().success();
+    assert_eq!(
+        common::read_file(&project, "src/components/card.tsx"),
+        "import type { ReactNode } from 'react'
+
+type CardProps = {
+\tchildren?: ReactNode
+}
+
+export default function Card({ children }: CardProps) {
+\treturn (
+\t\t<div>
+\t\t\t<h1>Hi! \\o/</h1>
+\t\t\t{children}
+\t\t</div>
+\t)
+}
+"
+    );
+}
+
+#[test]
+fn test_editorconfig() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        ".editorconfig",
+        "root = true
+
+[*]
+end_of_line = crlf
+
+[*.{js,jsx}]
+indent_size = 2
+insert_final_newline = true
+trim_trailing_whitespace = true
+",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.jsx.hbs",
+        "export default function {{name}}() {  \n    return <div />;\n}",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "card"]);
+    cmd.assert().success();
+    assert_eq!(
+        common::read_file(&project, "src/components/card.jsx"),
+        "export default function Card() {\r\n  return <div />;\r\n}\r\n"
+    );
+
+    // Quotes and semicolons of custom templates are left as they are
+    common::write_file(&project, ".prettierrc.json", r#"{ "semi": false }"#);
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "other", "--template", "card"]);
+    cmd.assert().success();
+    assert_eq!(
+        common::read_file(&project, "src/components/other.jsx"),
+        "export default function Other() {\r\n  return <div />;\r\n}\r\n"
+    );
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+/**
+ *  The path of the new files can be defined with a pattern in the
+ *  configuration file or in the template's front-matter.
+ * */
+
+#[test]
+fn test_config_path_pattern() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{
+            "new": {
+                "component": {
+                    "jsx": true,
+                    "path": "src/features/{{segment 0}}/components/{{pascal name}}.{{ext}}"
+                },
+                "style": { "path": "{{folder}}/{{kebab name}}/{{name}}.{{ext}}" }
+            }
+        }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "billing/invoice-row"]);
+    cmd.assert().success();
+    assert!(project
+        .path()
+        .join("src/features/billing/components/InvoiceRow.jsx")
+        .is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "style", "MainLayout", "--folder", "styles"]);
+    cmd.assert().success();
+    assert!(project
+        .path()
+        .join("src/styles/main-layout/MainLayout.css")
+        .is_file());
+}
+
+#[test]
+fn test_default_style_path() {
+    let project = common::new_project();
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "style", "card", "--folder", "styles"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/styles/card/card.css").is_file());
+}
+
+#[test]
+fn test_path_outside_the_project() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": { "path": "../{{name}}.{{ext}}" } } }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("must be inside the project"));
+}
+
+#[test]
+fn test_path_collision() {
+    let project = common::new_project();
+    common::write_file(&project, "src/components/card.jsx", "");
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--jsx"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("src/components/card.jsx already exists"));
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--tsx"]);
+    cmd.assert().failure().stderr(
+        contains("src/components/card.tsx collides with the existing file")
+            .and(contains("card.jsx")),
+    );
+}
+
+#[test]
+fn test_component_next_to_its_stylesheet() {
+    let project = common::new_project();
+    common::write_file(&project, "src/components/Card.css", "");
+    common::write_file(&project, "src/components/Card.test.js", "");
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "Card", "--jsx"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/Card.jsx").is_file());
+}
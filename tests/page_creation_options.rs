@@ -1,10 +1,7 @@
-use std::path::Path;
-
-use assert_cmd::Command;
+mod common;
 
 /**
- *  Before testing, is advised to remove all files and folders from <path/to/crate>/src/app/
- *  and <path/to/crate>/src/pages/
+ *  Every test runs inside its own temporary project. See tests/common/
  *
  *  As of version 0.1.0, all pages are created based on the app router
  *  by default.
@@ -12,12 +9,13 @@ use assert_cmd::Command;
 
 #[test]
 fn test_page_router_option() {
-    let mut cmd = Command::cargo_bin("next-butler").unwrap();
+    let project = common::new_project();
+    let mut cmd = common::nb(&project);
 
     let page_to_create = "/my/test_router";
     cmd.args(["new", "page", page_to_create, "--page-router"]);
     cmd.assert().success();
-    let expected_page_path = Path::new("src/pages/my/test_router.jsx");
+    let expected_page_path = project.path().join("src/pages/my/test_router.jsx");
     assert!(
         expected_page_path.is_file(),
         "[{}] not created at the correct location",
@@ -30,13 +28,25 @@ fn test_page_router_option() {
  * */
 #[test]
 fn test_page_custom_template() {
-    let mut cmd = Command::cargo_bin("next-butler").unwrap();
+    let project = common::new_project();
+    let mut cmd = common::nb(&project);
+
+    common::write_file(
+        &project,
+        "nextbutler/templates/pages/test_template.hbs",
+        "export default function {{ name }}() {}",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "page": { "typescript": true, "jsx": true } } }"#,
+    );
 
     let page_to_create = "/my/test_template";
     let template_to_use = "test_template";
     cmd.args(["new", "page", page_to_create, "--template", template_to_use]);
     cmd.assert().success();
-    let expected_page_path = Path::new("src/app/my/test_template/page.tsx");
+    let expected_page_path = project.path().join("src/app/my/test_template/page.tsx");
     assert!(
         expected_page_path.is_file(),
         "[{}] not created at the correct location",
@@ -44,9 +54,48 @@ fn test_page_custom_template() {
     );
 }
 
+#[test]
+fn test_page_template_extension_from_name() {
+    let project = common::new_project();
+    let mut cmd = common::nb(&project);
+
+    common::write_file(
+        &project,
+        "nextbutler/templates/pages/test_template.tsx.hbs",
+        "export default function {{ name }}() {}",
+    );
+
+    cmd.args(["new", "page", "/my/test_template", "--template", "test_template"]);
+    cmd.assert().success();
+    assert!(project
+        .path()
+        .join("src/app/my/test_template/page.tsx")
+        .is_file());
+}
+
+#[test]
+fn test_page_template_from_user_layer() {
+    let project = common::new_project();
+    let mut cmd = common::nb(&project);
+
+    common::write_file(
+        &project,
+        ".config/next-butler/templates/pages/test_template.jsx.hbs",
+        "user",
+    );
+
+    cmd.args(["new", "page", "/my/test_template", "--template", "test_template"]);
+    cmd.assert().success();
+    assert_eq!(
+        common::read_file(&project, "src/app/my/test_template/page.jsx"),
+        "user"
+    );
+}
+
 #[test]
 fn test_page_extension_conflict() {
-    let mut cmd = Command::cargo_bin("next-butler").unwrap();
+    let project = common::new_project();
+    let mut cmd = common::nb(&project);
 
     let page_to_create = "/my/test_ext_conflict";
     cmd.args(["new", "page", page_to_create, "--tsx", "--jsx"]);
@@ -0,0 +1,71 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+const PRESETS_CONFIG: &str = r#"{
+    "new": { "page": { "typescript": false, "jsx": true } },
+    "presets": {
+        "admin": { "new": { "page": { "typescript": true, "page_router": true } } },
+        "marketing": { "new": { "page": { "jsx": true } } }
+    }
+}"#;
+
+/**
+ *  Presets overlay the base configuration when chosen with --preset, or
+ *  through the default_preset key.
+ * */
+#[test]
+fn test_preset_flag() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/nextbutler.json", PRESETS_CONFIG);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "about"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/app/about/page.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "users", "--preset", "admin"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/pages/users.tsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["config", "show", "--preset", "admin"]);
+    cmd.assert().success().stdout(
+        contains("\"typescript\": true")
+            .and(contains("\"page_router\": true"))
+            .and(contains("\"jsx\": true")),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "other", "--preset", "shop"]);
+    cmd.assert().failure().stderr(contains(
+        "Unknown preset 'shop'. The configuration's presets are: admin, marketing",
+    ));
+}
+
+#[test]
+fn test_default_preset() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        &PRESETS_CONFIG.replacen('{', r#"{ "default_preset": "admin","#, 1),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "users"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/pages/users.tsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "about", "--preset", "marketing"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/app/about/page.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["config", "show", "--resolved", "--origin"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("new.page.typescript = true  # preset (admin)"));
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+/**
+ *  The project's folders can be set in the configuration file. Otherwise,
+ *  they are guessed like Next.js does.
+ * */
+
+#[test]
+fn test_root_routers_next_to_unrelated_src() {
+    let project = tempfile::tempdir().unwrap();
+    common::write_file(&project, "src/main.rs", "");
+    common::write_file(&project, "app/layout.jsx", "");
+    common::write_file(&project, "components/.gitkeep", "");
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "about", "--jsx"]);
+    cmd.assert().success();
+    assert!(project.path().join("app/about/page.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--jsx"]);
+    cmd.assert().success();
+    assert!(project.path().join("components/card.jsx").is_file());
+}
+
+#[test]
+fn test_config_dirs() {
+    let project = tempfile::tempdir().unwrap();
+    common::write_file(&project, "web/routes/.gitkeep", "");
+    common::write_file(&project, "ui/.gitkeep", "");
+    common::write_file(&project, "web/styles/.gitkeep", "");
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{
+            "src_dir": "web",
+            "app_dir": "web/routes",
+            "public_dir": "static",
+            "components_dir": "ui",
+            "new": { "style": { "path": "{{public_dir}}/css/{{name}}.{{ext}}" } }
+        }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "page", "about", "--jsx"]);
+    cmd.assert().success();
+    assert!(project.path().join("web/routes/about/page.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--jsx"]);
+    cmd.assert().success();
+    assert!(project.path().join("ui/card.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "style", "main", "--folder", "styles"]);
+    cmd.assert().success();
+    assert!(project.path().join("static/css/main.css").is_file());
+}
+
+#[test]
+fn test_config_dirs_outside_project() {
+    let project = common::new_project();
+    let outside_dir = tempfile::tempdir().unwrap();
+
+    for (setting, dir) in [
+        ("src_dir", "../web"),
+        ("app_dir", "src/../../app"),
+        ("components_dir", outside_dir.path().to_str().unwrap()),
+    ] {
+        common::write_file(
+            &project,
+            "nextbutler/nextbutler.json",
+            &serde_json::json!({ setting: dir }).to_string(),
+        );
+
+        let mut cmd = common::nb(&project);
+        cmd.args(["new", "component", "card", "--jsx"]);
+        cmd.assert().failure().stderr(
+            contains(format!("The {} ", setting)).and(contains("must be inside the project")),
+        );
+    }
+    assert!(!project.path().join("src/components/card.jsx").exists());
+}
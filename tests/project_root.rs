@@ -0,0 +1,72 @@
+mod common;
+
+use predicates::str::contains;
+
+/**
+ *  The tool works from any directory of the project, resolving the paths
+ *  against the project's root.
+ * */
+
+#[test]
+fn test_run_from_subdirectory() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": { "typescript": true, "jsx": true } } }"#,
+    );
+    common::write_file(&project, "src/app/dashboard/vars.json", r#"{ "owner": "ACME" }"#);
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.tsx.hbs",
+        "// {{owner}}\n",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.current_dir(project.path().join("src/app/dashboard"));
+    cmd.args(["new", "component", "card", "--template", "card", "--vars", "vars.json"]);
+    cmd.assert().success();
+    assert_eq!(common::read_file(&project, "src/components/card.tsx"), "// ACME\n");
+
+    let mut cmd = common::nb(&project);
+    cmd.current_dir(project.path().join("src/app/dashboard"));
+    cmd.args(["template", "diff", "../../components/card.tsx"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("src/components/card.tsx matches its template"));
+}
+
+#[test]
+fn test_package_json_root() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "package.json",
+        r#"{ "dependencies": { "next": "15.0.0", "react": "19.0.0" } }"#,
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.current_dir(project.path().join("src/styles"));
+    cmd.args(["new", "style", "card", "--folder", "styles"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/styles/card/card.css").is_file());
+}
+
+#[test]
+fn test_root_option() {
+    let project = common::new_project();
+    let other_dir = tempfile::tempdir().unwrap();
+
+    let mut cmd = common::nb(&project);
+    cmd.current_dir(other_dir.path());
+    cmd.args(["new", "component", "card", "--jsx", "--root"])
+        .arg(project.path());
+    cmd.assert().success();
+    assert!(project.path().join("src/components/card.jsx").is_file());
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["--root", "missing", "new", "component", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("missing is not a directory"));
+}
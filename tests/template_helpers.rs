@@ -0,0 +1,74 @@
+mod common;
+
+use next_butler::helpers::str_helper::{pluralize, singularize};
+use next_butler::template::helpers::relative_import;
+
+#[test]
+fn test_case_helpers() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/cases.hbs",
+        "{{pascal name}} {{camel name}} {{kebab name}} {{snake name}} {{upper name}}",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "user_card", "--template", "cases"]);
+    cmd.assert().success();
+
+    assert_eq!(
+        common::read_file(&project, "src/components/user_card.jsx"),
+        "UserCard userCard user-card user_card USERCARD"
+    );
+}
+
+#[test]
+fn test_conditional_helpers() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/cond.hbs",
+        "{{#if (and (eq name \"Button\") (or false true))}}yes{{else}}no{{/if}}",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "button", "--template", "cond"]);
+    cmd.assert().success();
+
+    assert_eq!(
+        common::read_file(&project, "src/components/button.jsx"),
+        "yes"
+    );
+}
+
+#[test]
+fn test_pluralization() {
+    assert_eq!(pluralize("UserProfile"), "UserProfiles");
+    assert_eq!(pluralize("category"), "categories");
+    assert_eq!(pluralize("box"), "boxes");
+    assert_eq!(pluralize("Person"), "People");
+    assert_eq!(singularize("categories"), "category");
+    assert_eq!(singularize("Boxes"), "Box");
+    assert_eq!(singularize("AdminPeople"), "AdminPerson");
+    assert_eq!(singularize("address"), "address");
+    assert_eq!(singularize("Responses"), "Response");
+    assert_eq!(singularize("Courses"), "Course");
+    assert_eq!(singularize("Databases"), "Database");
+    assert_eq!(singularize("Classes"), "Class");
+    assert_eq!(singularize("Status"), "Status");
+    assert_eq!(singularize("OrderStatuses"), "OrderStatus");
+    assert_eq!(singularize("bus"), "bus");
+    assert_eq!(pluralize("status"), "statuses");
+}
+
+#[test]
+fn test_relative_import() {
+    assert_eq!(
+        relative_import("src/components/cards/Card.tsx", "src/lib/utils.ts"),
+        "../../lib/utils"
+    );
+    assert_eq!(
+        relative_import("src/components/Card.tsx", "src/components/Card.module.css"),
+        "./Card.module.css"
+    );
+}
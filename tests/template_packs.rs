@@ -0,0 +1,71 @@
+mod common;
+
+use predicates::str::contains;
+
+#[test]
+fn test_pack_and_install_archive() {
+    let design_system = common::new_project();
+    common::write_file(
+        &design_system,
+        "nextbutler/templates/components/card.tsx.hbs",
+        "{{> header}}export default function {{name}}() {}",
+    );
+    common::write_file(
+        &design_system,
+        "nextbutler/templates/partials/header.hbs",
+        "// ACME\n",
+    );
+
+    let mut cmd = common::nb(&design_system);
+    cmd.args(["template", "pack", "--name", "acme", "--version", "1.2.0"]);
+    cmd.assert().success();
+    let archive = design_system.path().join("acme-1.2.0.tar.gz");
+    assert!(archive.is_file());
+
+    let app = common::new_project();
+    let mut cmd = common::nb(&app);
+    cmd.args(["template", "install", archive.to_str().unwrap()]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Template pack 'acme' (1.2.0) successfully installed"));
+
+    let mut cmd = common::nb(&app);
+    cmd.args(["new", "component", "card", "--tsx", "--template", "acme/card", "--explain"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("resolved from the 'acme' pack templates"));
+    assert_eq!(
+        common::read_file(&app, "src/components/card.tsx"),
+        "// ACME\nexport default function Card() {}"
+    );
+
+    let mut cmd = common::nb(&app);
+    cmd.args(["new", "page", "/home", "--template", "acme/home"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("The pack 'acme' doesn't provide pages templates"));
+}
+
+#[test]
+fn test_install_configured_packs() {
+    let app = common::new_project();
+    common::write_file(
+        &app,
+        "vendor/acme/nextbutler-pack.json",
+        r#"{ "name": "acme", "version": "2.0.0", "kinds": ["stylesheets"] }"#,
+    );
+    common::write_file(&app, "vendor/acme/stylesheets/module.css.hbs", ".{{name}} {}");
+    common::write_file(
+        &app,
+        "nextbutler/nextbutler.json",
+        r#"{ "template_packs": ["vendor/acme"] }"#,
+    );
+
+    let mut cmd = common::nb(&app);
+    cmd.args(["template", "install"]);
+    cmd.assert().success();
+    assert!(app
+        .path()
+        .join("nextbutler/packs/acme/stylesheets/module.css.hbs")
+        .is_file());
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+
+const TEMPLATE: &str = "import React from \"react\";
+
+export default function {{name}}() {
+  return <div />;
+}
+";
+
+/**
+ *  Generated files are recorded in nextbutler/manifest.json, so they can be
+ *  updated when their template changes.
+ * */
+
+#[test]
+fn test_update_merges_template_changes() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/templates/components/card.jsx.hbs", TEMPLATE);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "card"]);
+    cmd.assert().success();
+    let manifest = common::read_file(&project, "nextbutler/manifest.json");
+    assert!(
+        contains("\"src/components/card.jsx\"")
+            .and(contains("\"template\": \"nextbutler/templates/components/card.jsx.hbs\""))
+            .and(contains("\"name\": \"Card\""))
+            .eval(&manifest),
+        "{}",
+        manifest
+    );
+
+    // Edit the generated file and the template in different lines
+    common::write_file(
+        &project,
+        "src/components/card.jsx",
+        &common::read_file(&project, "src/components/card.jsx")
+            .replace("<div />", "<div>Edited</div>"),
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.jsx.hbs",
+        &TEMPLATE.replace("import React from \"react\";\n", "// {{name}}\n"),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "update", "--kind", "component"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Updated src/components/card.jsx"));
+    assert_eq!(
+        common::read_file(&project, "src/components/card.jsx"),
+        "// Card\n\nexport default function Card() {\n  return <div>Edited</div>;\n}\n"
+    );
+
+    // Nothing changed since the last update
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "update"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1 files were already up to date"));
+}
+
+#[test]
+fn test_update_leaves_conflict_markers() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/templates/components/card.jsx.hbs", TEMPLATE);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "card"]);
+    cmd.assert().success();
+
+    common::write_file(
+        &project,
+        "src/components/card.jsx",
+        &common::read_file(&project, "src/components/card.jsx").replace("<div />", "<span />"),
+    );
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.jsx.hbs",
+        &TEMPLATE.replace("<div />", "<section />"),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "update"]);
+    cmd.assert()
+        .failure()
+        .stdout(contains("Conflicts in src/components/card.jsx"));
+    let content = common::read_file(&project, "src/components/card.jsx");
+    assert!(
+        contains("<<<<<<<")
+            .and(contains("<span />"))
+            .and(contains("<section />"))
+            .eval(&content),
+        "{}",
+        content
+    );
+}
+
+#[test]
+fn test_diff_and_drift() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/templates/components/card.jsx.hbs", TEMPLATE);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "card"]);
+    cmd.assert().success();
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "other", "--template", "card"]);
+    cmd.assert().success();
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "drift"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Every generated file matches its template"));
+
+    common::write_file(
+        &project,
+        "src/components/card.jsx",
+        &common::read_file(&project, "src/components/card.jsx").replace("<div />", "<span />"),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "diff", "src/components/card.jsx"]);
+    cmd.assert().success().stdout(
+        contains("+++ src/components/card.jsx")
+            .and(contains("-  return <div />;"))
+            .and(contains("+  return <span />;")),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "drift"]);
+    cmd.assert()
+        .failure()
+        .stdout(
+            contains("src/components/card.jsx: it differs from its template")
+                .and(contains("other.jsx").not()),
+        )
+        .stderr(contains("1 generated files don't match their template"));
+}
+
+#[test]
+fn test_update_after_partial_change() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/templates/partials/header.hbs", "// v1\n");
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.jsx.hbs",
+        &format!("{{{{> header}}}}{}", TEMPLATE),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "card"]);
+    cmd.assert().success();
+
+    common::write_file(&project, "nextbutler/templates/partials/header.hbs", "// v2\n");
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "update"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Updated src/components/card.jsx"));
+    assert!(common::read_file(&project, "src/components/card.jsx").starts_with("// v2\n"));
+}
+
+#[test]
+fn test_template_change_without_render_change() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/templates/components/card.jsx.hbs", TEMPLATE);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--template", "card"]);
+    cmd.assert().success();
+    let manifest = common::read_file(&project, "nextbutler/manifest.json");
+    assert!(contains("\"template_hash\"").eval(&manifest), "{}", manifest);
+
+    // A comment changes the template but not what it renders
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/card.jsx.hbs",
+        &format!("{{{{!-- Shared card --}}}}{}", TEMPLATE),
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "drift"]);
+    cmd.assert()
+        .failure()
+        .stdout(contains(
+            "src/components/card.jsx: its template changed since it was generated",
+        ));
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "update"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Recorded src/components/card.jsx"));
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "drift"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Every generated file matches its template"));
+}
+
+#[test]
+fn test_no_manifest_without_tool_dir() {
+    let project = common::new_project();
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(project.path().join("src/components/card.jsx").is_file());
+    assert!(!project.path().join("nextbutler").exists());
+}
+
+#[test]
+fn test_rm_forgets_generated_files() {
+    let project = common::new_project();
+    common::write_file(&project, "nextbutler/templates/components/card.jsx.hbs", TEMPLATE);
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "ui/card", "--template", "card"]);
+    cmd.assert().success();
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "other", "--template", "card"]);
+    cmd.assert().success();
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["rm", "component", "ui"]);
+    cmd.assert().success();
+
+    let manifest = common::read_file(&project, "nextbutler/manifest.json");
+    assert!(
+        contains("ui/card.jsx").not().and(contains("other.jsx")).eval(&manifest),
+        "{}",
+        manifest
+    );
+    let mut cmd = common::nb(&project);
+    cmd.args(["template", "drift"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("Every generated file matches its template"));
+}
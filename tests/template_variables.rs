@@ -0,0 +1,78 @@
+mod common;
+
+#[test]
+fn test_variables_precedence() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/vars.hbs",
+        "{{name}} {{owner}} {{ui}} {{year}}",
+    );
+    common::write_file(
+        &project,
+        "nextbutler/nextbutler.json",
+        r#"{ "new": { "component": { "variables": { "owner": "ACME", "ui": "@acme/ui" } } } }"#,
+    );
+    common::write_file(&project, "vars.json", r#"{ "ui": "@acme/ds", "year": 2024 }"#);
+
+    let mut cmd = common::nb(&project);
+    cmd.args([
+        "new",
+        "component",
+        "card",
+        "--jsx",
+        "--template",
+        "vars",
+        "--vars",
+        "vars.json",
+        "--var",
+        "year=2025",
+        "--var",
+        "name=Overridden",
+    ]);
+    cmd.assert().success();
+
+    assert_eq!(
+        common::read_file(&project, "src/components/card.jsx"),
+        "Overridden ACME @acme/ds 2025"
+    );
+}
+
+#[test]
+fn test_invalid_variable_argument() {
+    let project = common::new_project();
+
+    let mut cmd = common::nb(&project);
+    cmd.args(["new", "component", "card", "--var", "owner"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_variables_are_not_escaped() {
+    let project = common::new_project();
+    common::write_file(
+        &project,
+        "nextbutler/templates/components/raw.hbs",
+        "{{title}}|{{ui}}",
+    );
+
+    let mut cmd = common::nb(&project);
+    cmd.args([
+        "new",
+        "component",
+        "card",
+        "--jsx",
+        "--template",
+        "raw",
+        "--var",
+        r#"title=Tom's "best" & <co>"#,
+        "--var",
+        "ui=@acme/ui?x=1",
+    ]);
+    cmd.assert().success();
+
+    assert_eq!(
+        common::read_file(&project, "src/components/card.jsx"),
+        r#"Tom's "best" & <co>|@acme/ui?x=1"#
+    );
+}
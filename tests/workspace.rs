@@ -0,0 +1,125 @@
+mod common;
+
+use predicates::{prelude::*, str::contains};
+use tempfile::TempDir;
+
+fn new_workspace() -> TempDir {
+    let workspace = tempfile::tempdir().unwrap();
+    common::write_file(
+        &workspace,
+        "nextbutler/nextbutler.json",
+        r#"{
+            "workspace": {
+                "apps": { "web": "apps/web", "admin": "apps/admin" },
+                "packages": { "ui": "packages/ui" }
+            },
+            "new": { "component": { "typescript": true, "jsx": true } }
+        }"#,
+    );
+    for app_dir in ["apps/web", "apps/admin"] {
+        std::fs::create_dir_all(workspace.path().join(app_dir).join("src/components")).unwrap();
+    }
+    std::fs::create_dir_all(workspace.path().join("packages/ui/src/components")).unwrap();
+    workspace
+}
+
+/**
+ *  A workspace's configuration lists its apps and packages, and holds the
+ *  settings they share.
+ * */
+
+#[test]
+fn test_app_from_cwd() {
+    let workspace = new_workspace();
+    common::write_file(
+        &workspace,
+        "apps/admin/nextbutler/nextbutler.json",
+        r#"{ "new": { "component": { "typescript": false } } }"#,
+    );
+
+    let mut cmd = common::nb(&workspace);
+    cmd.current_dir(workspace.path().join("apps/web/src"));
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(workspace.path().join("apps/web/src/components/card.tsx").is_file());
+
+    // The app's own settings override the shared ones
+    let mut cmd = common::nb(&workspace);
+    cmd.current_dir(workspace.path().join("apps/admin"));
+    cmd.args(["new", "component", "card"]);
+    cmd.assert().success();
+    assert!(workspace.path().join("apps/admin/src/components/card.jsx").is_file());
+}
+
+#[test]
+fn test_app_and_package_flags() {
+    let workspace = new_workspace();
+    common::write_file(
+        &workspace,
+        "nextbutler/templates/components/card.tsx.hbs",
+        "// shared {{name}}\n",
+    );
+
+    let mut cmd = common::nb(&workspace);
+    cmd.args(["new", "component", "card", "--template", "card", "--app", "admin"]);
+    cmd.assert().success();
+    assert_eq!(
+        common::read_file(&workspace, "apps/admin/src/components/card.tsx"),
+        "// shared Card\n"
+    );
+
+    let mut cmd = common::nb(&workspace);
+    cmd.current_dir(workspace.path().join("apps/web"));
+    cmd.args(["new", "component", "button", "--package", "ui"]);
+    cmd.assert().success();
+    assert!(workspace.path().join("packages/ui/src/components/button.tsx").is_file());
+}
+
+#[test]
+fn test_ambiguous_app() {
+    let workspace = new_workspace();
+
+    let mut cmd = common::nb(&workspace);
+    cmd.args(["new", "component", "card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("choose one with --app: admin, web"));
+
+    let mut cmd = common::nb(&workspace);
+    cmd.args(["new", "component", "card", "--app", "docs"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Unknown app 'docs'").and(contains("admin, web")));
+}
+
+#[test]
+fn test_unreadable_config_above_the_project() {
+    let outer = tempfile::tempdir().unwrap();
+    common::write_file(&outer, "package.json", "{bad");
+    common::write_file(&outer, "nested/nextbutler/nextbutler.json", "{}");
+    common::write_file(&outer, "nested/nextbutler/nextbutler.yaml", "");
+    common::write_file(
+        &outer,
+        "nested/proj/package.json",
+        r#"{ "dependencies": { "next": "14.0.0" } }"#,
+    );
+    std::fs::create_dir_all(outer.path().join("nested/proj/src/components")).unwrap();
+
+    let mut cmd = common::nb(&outer);
+    cmd.current_dir(outer.path().join("nested/proj"));
+    cmd.args(["new", "component", "Button"]);
+    cmd.assert().success();
+    assert!(outer
+        .path()
+        .join("nested/proj/src/components/Button.jsx")
+        .is_file());
+
+    // The project's own configuration is still checked
+    common::write_file(&outer, "nested/proj/nextbutler/nextbutler.json", "{bad");
+    let mut cmd = common::nb(&outer);
+    cmd.current_dir(outer.path().join("nested/proj"));
+    cmd.args(["new", "component", "Card"]);
+    cmd.assert()
+        .failure()
+        .stderr(contains("Invalid nextbutler/nextbutler.json"));
+}